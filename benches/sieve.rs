@@ -0,0 +1,75 @@
+//! Compares the library sieve with the `primes` crate.
+//!
+//! Run with `cargo bench --bench sieve`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use advent_of_code::sieve::Sieve;
+use primes::{PrimeSet, TrialDivision};
+
+/// Runs `f` `iterations` times and returns the mean duration of a run.
+fn measure<T>(iterations: u32, mut f: impl FnMut() -> T) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(f());
+    }
+    start.elapsed() / iterations
+}
+
+fn compare(
+    name: &str,
+    iterations: u32,
+    trial_division: impl FnMut() -> u64,
+    sieve: impl FnMut() -> u64,
+) {
+    let trial_division = measure(iterations, trial_division);
+    let sieve = measure(iterations, sieve);
+    println!("{name:<40} primes: {trial_division:>12.2?}   sieve: {sieve:>12.2?}");
+}
+
+fn main() {
+    // Day 2 used to list the primes up to the number of digits of each range, with a new generator per input
+    compare(
+        "primes up to 20, 1000 ranges",
+        100,
+        || {
+            let mut generator = TrialDivision::new();
+            (0..1000)
+                .map(|_| generator.iter().take_while(|&p| p <= 20).sum::<u64>())
+                .sum()
+        },
+        || {
+            let mut sieve = Sieve::new();
+            (0..1000)
+                .map(|_| sieve.primes_up_to(20).iter().sum::<u64>())
+                .sum()
+        },
+    );
+
+    compare(
+        "primes up to 1_000_000",
+        5,
+        || {
+            TrialDivision::new()
+                .iter()
+                .take_while(|&p| p <= 1_000_000)
+                .count() as u64
+        },
+        || Sieve::new().primes_up_to(1_000_000).len() as u64,
+    );
+
+    compare(
+        "factorize 1..100_000",
+        5,
+        || {
+            let mut generator = TrialDivision::new();
+            (1..100_000)
+                .map(|n| generator.prime_factors(n).len() as u64)
+                .sum()
+        },
+        || {
+            let mut sieve = Sieve::new();
+            (1..100_000).map(|n| sieve.factorize(n).len() as u64).sum()
+        },
+    );
+}
@@ -0,0 +1,192 @@
+use crate::grid::Grid;
+use crate::iter_utils::Neighborhood;
+
+/// A cellular automaton over a [`Grid`].
+///
+/// Every step, `rule(cell, neighbors)` computes the next state of a cell from its current state and the states of its
+/// neighbors, all cells being updated at once. Only the cells next to a cell that changed during the previous step
+/// are evaluated again, so steps only cost as much as the area that is still moving.
+/// The neighborhood must be symmetric, which all [`Neighborhood`]s are.
+pub struct Automaton<T, F> {
+    grid: Grid<T>,
+    neighborhood: Neighborhood,
+    rule: F,
+    generation: usize,
+    /// Cells to evaluate during the next step
+    frontier: Vec<(usize, usize)>,
+    /// `queued[index]` is true if `index` is in `frontier`
+    queued: Grid<bool>,
+    /// Cells that changed during the last step
+    changed: Vec<(usize, usize)>,
+}
+
+impl<T, F> Automaton<T, F>
+where
+    T: PartialEq,
+    F: FnMut(&T, &[&T]) -> T,
+{
+    /// Creates an automaton starting from `grid`, the first step evaluates every cell.
+    pub fn new(grid: Grid<T>, neighborhood: Neighborhood, rule: F) -> Self {
+        let frontier = grid.indexed_iter().map(|(index, _)| index).collect();
+        let queued = Grid::from_elem(grid.shape(), true);
+
+        Self {
+            grid,
+            neighborhood,
+            rule,
+            generation: 0,
+            frontier,
+            queued,
+            changed: vec![],
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    /// Number of steps run so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Returns `true` if the last step didn't change any cell, i.e. the automaton reached a fixpoint.
+    pub fn is_stable(&self) -> bool {
+        self.frontier.is_empty()
+    }
+
+    /// Runs a single step and returns the indices of the cells that changed. Does nothing if the automaton is stable.
+    pub fn step(&mut self) -> &[(usize, usize)] {
+        self.changed.clear();
+        if self.frontier.is_empty() {
+            return &self.changed;
+        }
+
+        // Compute the new states from the current grid
+        let mut updates = vec![];
+        let mut neighbors = vec![];
+        for &index in &self.frontier {
+            self.queued[index] = false;
+
+            neighbors.clear();
+            neighbors.extend(
+                self.grid
+                    .neighbors(index, self.neighborhood)
+                    .map(|neighbor| &self.grid[neighbor]),
+            );
+
+            let cell = &self.grid[index];
+            let new_cell = (self.rule)(cell, &neighbors);
+            if new_cell != *cell {
+                updates.push((index, new_cell));
+            }
+        }
+
+        // Apply them and queue the changed cells and their neighbors for the next step
+        self.frontier.clear();
+        for (index, new_cell) in updates {
+            self.grid[index] = new_cell;
+            self.changed.push(index);
+
+            for cell in self
+                .grid
+                .neighbors(index, self.neighborhood)
+                .chain([index])
+            {
+                if !self.queued[cell] {
+                    self.queued[cell] = true;
+                    self.frontier.push(cell);
+                }
+            }
+        }
+
+        self.generation += 1;
+        &self.changed
+    }
+
+    /// Runs at most `steps` steps, stopping early at a fixpoint.
+    /// After each step that changed something, calls `on_step(generation, grid, changed)`.
+    /// Returns the number of steps that changed something.
+    pub fn run_with(
+        &mut self,
+        steps: usize,
+        mut on_step: impl FnMut(usize, &Grid<T>, &[(usize, usize)]),
+    ) -> usize {
+        for run in 0..steps {
+            self.step();
+            if self.changed.is_empty() {
+                return run;
+            }
+            on_step(self.generation, &self.grid, &self.changed);
+        }
+        steps
+    }
+
+    /// Same as [`Automaton::run_with`] without callback.
+    pub fn run(&mut self, steps: usize) -> usize {
+        self.run_with(steps, |_, _, _| ())
+    }
+
+    /// Runs until a step doesn't change any cell, calling `on_step` like [`Automaton::run_with`].
+    /// Never returns if the automaton oscillates.
+    pub fn run_until_fixpoint_with(
+        &mut self,
+        on_step: impl FnMut(usize, &Grid<T>, &[(usize, usize)]),
+    ) -> usize {
+        self.run_with(usize::MAX, on_step)
+    }
+
+    /// Same as [`Automaton::run_until_fixpoint_with`] without callback.
+    pub fn run_until_fixpoint(&mut self) -> usize {
+        self.run_until_fixpoint_with(|_, _, _| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_of_life(&alive: &bool, neighbors: &[&bool]) -> bool {
+        let alive_neighbors = neighbors.iter().filter(|&&&neighbor| neighbor).count();
+        alive_neighbors == 3 || (alive && alive_neighbors == 2)
+    }
+
+    #[test]
+    fn test_oscillator() {
+        let blinker = Grid::parse(".....\n..#..\n..#..\n..#..\n.....", |c| c == '#');
+        let mut automaton = Automaton::new(blinker.clone(), Neighborhood::All, game_of_life);
+
+        assert_eq!(automaton.step().len(), 4);
+        assert_eq!(
+            automaton.grid().render(|&alive| if alive { '#' } else { '.' }),
+            ".....\n.....\n.###.\n.....\n....."
+        );
+        assert_eq!(automaton.run(1), 1);
+        assert_eq!(automaton.grid(), &blinker);
+        assert_eq!(automaton.generation(), 2);
+        assert!(!automaton.is_stable());
+    }
+
+    #[test]
+    fn test_fixpoint() {
+        // Erodes every cell with less than 2 orthogonal neighbors
+        let grid = Grid::parse("###.\n#...\n...#", |c| c == '#');
+        let mut automaton = Automaton::new(grid, Neighborhood::Orthogonal, |&cell, neighbors| {
+            cell && neighbors.iter().filter(|&&&neighbor| neighbor).count() >= 2
+        });
+
+        let mut removed = vec![];
+        let steps = automaton.run_until_fixpoint_with(|generation, _, changed| {
+            removed.push((generation, changed.len()))
+        });
+
+        assert_eq!(steps, 2);
+        assert_eq!(removed, vec![(1, 3), (2, 2)]);
+        assert!(automaton.is_stable());
+        assert_eq!(automaton.into_grid().iter().filter(|&&cell| cell).count(), 0);
+    }
+}
@@ -0,0 +1,466 @@
+/// Arbitrary-precision unsigned integer, for answers that don't fit in a `u64`.
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::Display;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Rem};
+use std::str::FromStr;
+
+/// Arbitrary-precision unsigned integer supporting addition, multiplication and euclidean division.
+///
+/// It implements [`Display`], so solutions can return it as their answer like any primitive integer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Little-endian base 2^32 digits, without trailing zeros so that zero is empty
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self { limbs: vec![1] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Removes the trailing zero limbs.
+    fn normalize(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    /// Returns the value as a `u128`, `None` if it doesn't fit.
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0, |acc, &limb| (acc << 32) | limb as u128),
+        )
+    }
+
+    /// Returns the value as a `u64`, `None` if it doesn't fit.
+    pub fn to_u64(&self) -> Option<u64> {
+        self.to_u128()?.try_into().ok()
+    }
+
+    /// Number of significant bits, `0` for zero.
+    pub fn bits(&self) -> u64 {
+        self.limbs.last().map_or(0, |&last| {
+            32 * (self.limbs.len() as u64 - 1) + (32 - last.leading_zeros()) as u64
+        })
+    }
+
+    fn bit(&self, index: u64) -> bool {
+        self.limbs
+            .get((index / 32) as usize)
+            .is_some_and(|&limb| limb >> (index % 32) & 1 == 1)
+    }
+
+    /// Computes `self * factor + term` in place.
+    fn mul_add_small(&mut self, factor: u32, term: u32) {
+        let mut carry = term as u64;
+        for limb in &mut self.limbs {
+            let value = *limb as u64 * factor as u64 + carry;
+            *limb = value as u32;
+            carry = value >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        *self = std::mem::take(self).normalize();
+    }
+
+    /// Divides in place by `divisor` and returns the remainder.
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let value = (remainder << 32) | *limb as u64;
+            *limb = (value / divisor as u64) as u32;
+            remainder = value % divisor as u64;
+        }
+        *self = std::mem::take(self).normalize();
+        remainder as u32
+    }
+
+    /// Subtracts `other` in place, which must not be greater than `self`.
+    fn sub_assign(&mut self, other: &BigUint) {
+        let mut borrow = false;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let rhs = other.limbs.get(i).copied().unwrap_or(0);
+            if rhs == 0 && !borrow && i >= other.limbs.len() {
+                break;
+            }
+            let (value, overflow_1) = limb.overflowing_sub(rhs);
+            let (value, overflow_2) = value.overflowing_sub(borrow as u32);
+            *limb = value;
+            borrow = overflow_1 || overflow_2;
+        }
+        debug_assert!(!borrow, "attempt to subtract with overflow");
+        *self = std::mem::take(self).normalize();
+    }
+
+    /// Shifts left by one bit and sets the lowest bit to `bit`.
+    fn shl_one(&mut self, bit: bool) {
+        let mut carry = bit as u32;
+        for limb in &mut self.limbs {
+            let next = *limb >> 31;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        if carry > 0 {
+            self.limbs.push(carry);
+        }
+    }
+
+    /// Returns the quotient and the remainder of the euclidean division by `divisor`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is zero.
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+
+        if let [small] = divisor.limbs[..] {
+            let mut quotient = self.clone();
+            let remainder = quotient.div_rem_small(small);
+            return (quotient, BigUint::from(remainder));
+        }
+        if self < divisor {
+            return (BigUint::zero(), self.clone());
+        }
+
+        // Schoolbook long division in base 2
+        let mut quotient = BigUint {
+            limbs: vec![0; self.limbs.len()],
+        };
+        let mut remainder = BigUint::zero();
+        for index in (0..self.bits()).rev() {
+            remainder.shl_one(self.bit(index));
+            if remainder >= *divisor {
+                remainder.sub_assign(divisor);
+                quotient.limbs[(index / 32) as usize] |= 1 << (index % 32);
+            }
+        }
+
+        (quotient.normalize(), remainder)
+    }
+
+    pub fn pow(&self, mut exp: u32) -> BigUint {
+        let mut base = self.clone();
+        let mut ret = BigUint::one();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                ret *= &base;
+            }
+            base = &base * &base;
+            exp >>= 1;
+        }
+
+        ret
+    }
+}
+
+macro_rules! impl_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(value: $t) -> Self {
+                    let mut value = value as u128;
+                    let mut limbs = vec![];
+                    while value > 0 {
+                        limbs.push(value as u32);
+                        value >>= 32;
+                    }
+                    Self { limbs }
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(u8, u16, u32, u64, u128, usize);
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let (long, short) = if self.limbs.len() >= rhs.limbs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        let mut limbs = Vec::with_capacity(long.limbs.len() + 1);
+        let mut carry = 0u64;
+        for (i, &limb) in long.limbs.iter().enumerate() {
+            let value = limb as u64 + short.limbs.get(i).copied().unwrap_or(0) as u64 + carry;
+            limbs.push(value as u32);
+            carry = value >> 32;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+
+        BigUint { limbs }
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        if self.is_zero() || rhs.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u32; self.limbs.len() + rhs.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in rhs.limbs.iter().enumerate() {
+                let value = a as u64 * b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = value as u32;
+                carry = value >> 32;
+            }
+            limbs[i + rhs.limbs.len()] = carry as u32;
+        }
+
+        BigUint { limbs }.normalize()
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).1
+    }
+}
+
+/// Implements the operators on owned values by forwarding to the implementation on references.
+macro_rules! forward_ops {
+    ($($trait:ident, $method:ident);*) => {
+        $(
+            impl $trait for BigUint {
+                type Output = BigUint;
+
+                fn $method(self, rhs: BigUint) -> BigUint {
+                    (&self).$method(&rhs)
+                }
+            }
+
+            impl $trait<&BigUint> for BigUint {
+                type Output = BigUint;
+
+                fn $method(self, rhs: &BigUint) -> BigUint {
+                    (&self).$method(rhs)
+                }
+            }
+        )*
+    };
+}
+
+forward_ops!(Add, add; Mul, mul; Div, div; Rem, rem);
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, rhs: &BigUint) {
+        *self = &*self + rhs;
+    }
+}
+
+impl AddAssign for BigUint {
+    fn add_assign(&mut self, rhs: BigUint) {
+        *self += &rhs;
+    }
+}
+
+impl MulAssign<&BigUint> for BigUint {
+    fn mul_assign(&mut self, rhs: &BigUint) {
+        *self = &*self * rhs;
+    }
+}
+
+impl MulAssign for BigUint {
+    fn mul_assign(&mut self, rhs: BigUint) {
+        *self *= &rhs;
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, value| acc + value)
+    }
+}
+
+impl Product for BigUint {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(BigUint::one(), |acc, value| acc * value)
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Groups of 9 decimal digits, least significant first
+        let mut groups = vec![];
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            groups.push(rest.div_rem_small(1_000_000_000));
+        }
+
+        let mut digits = groups.pop().unwrap_or(0).to_string();
+        for group in groups.iter().rev() {
+            digits.push_str(&format!("{group:09}"));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = BigUintFromStrError;
+
+    /// Parses a decimal number.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(BigUintFromStrError);
+        }
+
+        let mut ret = BigUint::zero();
+        for chunk in s.as_bytes().chunks(9) {
+            let value = chunk
+                .iter()
+                .fold(0, |acc, &digit| acc * 10 + (digit - b'0') as u32);
+            ret.mul_add_small(10u32.pow(chunk.len() as u32), value);
+        }
+        Ok(ret)
+    }
+}
+
+/// An error which can be returned when parsing a [`BigUint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigUintFromStrError;
+
+impl Error for BigUintFromStrError {}
+
+impl Display for BigUintFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expecting a non-empty string of decimal digits")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(BigUint::from(0u64), BigUint::zero());
+        assert_eq!(BigUint::from(u128::MAX).to_u128(), Some(u128::MAX));
+        assert_eq!(BigUint::from(u128::MAX).to_u64(), None);
+        assert_eq!(BigUint::from(42u8).to_u64(), Some(42));
+        assert_eq!(BigUint::from(u64::MAX).bits(), 64);
+
+        assert_eq!(big("0").to_string(), "0");
+        assert_eq!(big("000123").to_string(), "123");
+        assert_eq!(big("1000000000").to_string(), "1000000000");
+        assert_eq!(
+            big("340282366920938463463374607431768211456").to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(format!("{:>5}", BigUint::from(42u8)), "   42");
+        assert!("".parse::<BigUint>().is_err());
+        assert!("-1".parse::<BigUint>().is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let values = [
+            0u128,
+            1,
+            7,
+            1 << 32,
+            u32::MAX as u128,
+            u64::MAX as u128,
+            123456789012345678901,
+        ];
+        for a in values {
+            for b in values {
+                let (x, y) = (BigUint::from(a), BigUint::from(b));
+                assert_eq!((&x + &y).to_u128(), a.checked_add(b));
+                assert_eq!((&x * &y).to_u128(), a.checked_mul(b));
+                assert_eq!(x.cmp(&y), a.cmp(&b));
+                if let Some(quotient) = a.checked_div(b) {
+                    assert_eq!(
+                        x.div_rem(&y),
+                        (BigUint::from(quotient), BigUint::from(a % b))
+                    );
+                }
+            }
+        }
+
+        assert_eq!(
+            BigUint::from(2u8).pow(128),
+            big("340282366920938463463374607431768211456")
+        );
+        let factorial: BigUint = (1..=30u8).map(BigUint::from).product();
+        assert_eq!(factorial.to_string(), "265252859812191058636308480000000");
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let n = big("265252859812191058636308480000000123456789");
+        let d = big("98765432109876543210");
+        let (q, r) = n.div_rem(&d);
+        assert!(r < d);
+        assert_eq!(&q * &d + &r, n);
+        assert_eq!(q, big("2685685205296294883093"));
+        assert_eq!(
+            &n / &BigUint::from(10u8),
+            big("26525285981219105863630848000000012345678")
+        );
+        assert_eq!(&n % &BigUint::from(1000u16), BigUint::from(789u16));
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn test_div_by_zero() {
+        BigUint::one().div_rem(&BigUint::zero());
+    }
+}
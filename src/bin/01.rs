@@ -1,19 +1,14 @@
+use advent_of_code::scan;
 use iter_accumulate::IterAccumulate;
 
 advent_of_code::solution!(1);
 
 fn iterate_rotations(input: &str) -> impl Iterator<Item = i32> {
-    input.split("\n").filter_map(|rotation| {
-        if !rotation.is_empty() {
-            let rotation_amount: i32 = rotation[1..].parse().unwrap();
-
-            match rotation.chars().nth(0) {
-                Some('L') => Some(-rotation_amount),
-                Some('R') => Some(rotation_amount),
-                _ => unreachable!()
-            }
-        } else {
-            None
+    scan!(each input, "{char}{i32}", (char, i32)).map(|rotation| {
+        match rotation.unwrap() {
+            ('L', rotation_amount) => -rotation_amount,
+            ('R', rotation_amount) => rotation_amount,
+            _ => unreachable!()
         }
     })
 }
@@ -35,7 +30,7 @@ pub fn part_two(input: &str) -> Option<u64> {
     let mut cur_position = 50;
 
     for rotation in iterate_rotations(input) {
-        let num_turns = (rotation / 100).abs() as u64;
+        let num_turns = (rotation / 100).unsigned_abs() as u64;
         let remaining_steps = rotation % 100;
 
         num_zeros += num_turns;
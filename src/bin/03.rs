@@ -1,17 +1,10 @@
-use advent_of_code::int_utils::{self};
+use advent_of_code::{input, int_utils};
 use itertools::Itertools;
 
 advent_of_code::solution!(3);
 
 fn iter_banks(input: &str) -> impl Iterator<Item = Vec<u64>> {
-    input.split('\n')
-        .filter_map(|line| {
-            if !line.is_empty() {
-                Some(line.chars().map(|c| c.to_digit(10).unwrap() as u64).collect_vec())
-            } else {
-                None
-            }
-        })
+    input::non_empty_lines(input).map(|line| line.chars().map(|c| c.to_digit(10).unwrap() as u64).collect_vec())
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
@@ -28,9 +21,9 @@ pub fn part_one(input: &str) -> Option<u64> {
 
         // The least significant digit is the largest digit in range first_digit_index .. len
         let mut second_digit = bank[first_digit_index + 1];
-        for i in first_digit_index + 2 .. bank.len() {
-            if bank[i] > second_digit {
-                second_digit = bank[i];
+        for &digit in &bank[first_digit_index + 2 ..] {
+            if digit > second_digit {
+                second_digit = digit;
             }
         }
 
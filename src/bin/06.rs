@@ -1,7 +1,6 @@
-use std::iter;
-
-use itertools::Itertools;
-use ndarray::{Array, Axis};
+use advent_of_code::columns::{self, ColumnBlock};
+use advent_of_code::input;
+use advent_of_code::int_utils::CheckedAccumulate;
 
 advent_of_code::solution!(6);
 
@@ -11,87 +10,39 @@ enum Operation {
     Mul
 }
 
-fn generate_operator_list(line: &str) -> Vec<Operation> {
-    line.split_whitespace()
-        .filter_map(|character| match character {
-            "+" => Some(Operation::Add),
-            "*" => Some(Operation::Mul),
-            "" => None,
+/// Splits the worksheet into problems, each with its operator and the block of digits above it
+fn iter_problems(input: &str) -> impl Iterator<Item = (Operation, ColumnBlock)> {
+    columns::column_blocks(input::non_empty_lines(input)).into_iter().map(|mut block| {
+        let operation = match block.pop_row().unwrap().trim() {
+            "+" => Operation::Add,
+            "*" => Operation::Mul,
             _ => unreachable!()
-        }).collect_vec()
-}
-
-pub fn part_one(input: &str) -> Option<u64> {
-    let num_lines = input.chars().filter(|&c| c == '\n').count();
-    let mut line_iterator = input.split('\n').filter(|line| !line.is_empty());
-
-    // Construct value grid
-    let value_grid = {
-        // List of all lines
-        let lines = line_iterator.by_ref()
-            .take(num_lines - 1)
-            .map(|line| {
-                Array::from_iter(line.split_whitespace().filter_map(|string| string.parse::<u64>().ok()))
-            })
-            .collect_vec();
+        };
 
-        ndarray::stack(Axis(0), &lines.iter().map(|arr| arr.view()).collect_vec()).unwrap()
-    };
-
-    // Construct operator list
-    let operator_list = generate_operator_list(line_iterator.next().unwrap());
+        (operation, block)
+    })
+}
 
-    // Calculate total value
-    let total_value = iter::zip(value_grid.axis_iter(Axis(1)), operator_list.iter())
-        .map(|(values, operator)| {
-            match operator {
-                Operation::Add => values.sum(),
-                Operation::Mul => values.product()
-            }
-        })
-        .sum();
+/// Returns `None` on overflow
+fn solve(operation: &Operation, values: Vec<u64>) -> Option<u64> {
+    match operation {
+        Operation::Add => values.into_iter().checked_sum(),
+        Operation::Mul => values.into_iter().checked_product()
+    }
+}
 
-    Some(total_value)
+pub fn part_one(input: &str) -> Option<u64> {
+    // Numbers are written horizontally
+    iter_problems(input)
+        .map(|(operation, block)| solve(&operation, block.horizontal_numbers().unwrap()))
+        .try_fold(0u64, |total, value| total.checked_add(value?))
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let num_lines = input.chars().filter(|&c| c == '\n').count();
-    let mut line_iterator = input.split('\n').filter(|line| !line.is_empty());
-
-    // Construct character list
-    let char_grid = {
-        // List of all lines
-        let lines = line_iterator.by_ref()
-            .take(num_lines - 1)
-            .map(|line| {
-                Array::from_iter(line.chars())
-            })
-            .collect_vec();
-
-        ndarray::stack(Axis(0), &lines.iter().map(|arr| arr.view()).collect_vec()).unwrap()
-    };
-
-    // Construct operator list
-    let operator_list = generate_operator_list(line_iterator.next().unwrap());
-
-    // Iterators
-    let mut number_iterator = char_grid.axis_iter(Axis(1));
-
-    // Calculate total value
-    let total_value = operator_list.iter()
-        .map(|operator| {
-            let problem_value_iterator = number_iterator.by_ref()
-                .take_while(|value| value.iter().any(|&char| char != ' '))
-                .map(|value| value.iter().collect::<String>().trim().parse::<u64>().unwrap());
-
-            match &operator {
-                Operation::Add => problem_value_iterator.sum::<u64>(),
-                Operation::Mul => problem_value_iterator.product::<u64>(),
-            }
-        })
-        .sum();
-
-    Some(total_value)
+    // Numbers are written vertically, the order doesn't matter for sums and products
+    iter_problems(input)
+        .map(|(operation, block)| solve(&operation, block.vertical_numbers().unwrap()))
+        .try_fold(0u64, |total, value| total.checked_add(value?))
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use advent_of_code::grid::Grid;
 use itertools::Itertools;
 
 advent_of_code::solution!(7);
@@ -9,31 +10,31 @@ enum DiagramElem {
     Splitter
 }
 
-fn generate_grid(input: &str) -> impl Iterator<Item = impl Iterator<Item = DiagramElem>> {
-    input.split('\n')
-        .filter(|line| !line.is_empty())
-        .map(|line| line.chars().map(|char| match char {
-            'S' => DiagramElem::Start,
-            '.' => DiagramElem::Empty,
-            '^' => DiagramElem::Splitter,
-            _ => unreachable!()
-        }))
+fn generate_grid(input: &str) -> Grid<DiagramElem> {
+    Grid::parse(input, |char| match char {
+        'S' => DiagramElem::Start,
+        // Grid::parse pads ragged lines with spaces
+        '.' | ' ' => DiagramElem::Empty,
+        '^' => DiagramElem::Splitter,
+        _ => unreachable!()
+    })
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
-    // Diagram element iterator
-    let mut grid = generate_grid(input);
+    // Diagram element grid
+    let grid = generate_grid(input);
+    let mut lines = grid.rows();
 
     // beam_locations[i] == true if there is a beam at position i
-    let mut beam_locations = grid.next().unwrap().map(|elem| elem == DiagramElem::Start).collect_vec();
+    let mut beam_locations = lines.next().unwrap().iter().map(|elem| *elem == DiagramElem::Start).collect_vec();
 
     // How many splits we encountered
     let mut splits = 0;
 
-    for line in grid {
-        for (pos, element) in line.enumerate() {
+    for line in lines {
+        for (pos, element) in line.iter().enumerate() {
             // The beam encounters a splitter
-            if beam_locations[pos] && element == DiagramElem::Splitter {
+            if beam_locations[pos] && *element == DiagramElem::Splitter {
                 // Split the beam
                 beam_locations[pos] = false;
                 beam_locations[pos - 1] = true;
@@ -50,16 +51,17 @@ pub fn part_one(input: &str) -> Option<u64> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    // Diagram element iterator
-    let mut grid = generate_grid(input);
+    // Diagram element grid
+    let grid = generate_grid(input);
+    let mut lines = grid.rows();
 
     // superposed_beams[i] == j if there are j superposed beams at position i
-    let mut superposed_beams = grid.next().unwrap().map(|elem| (elem == DiagramElem::Start) as u64).collect_vec();
+    let mut superposed_beams = lines.next().unwrap().iter().map(|elem| (*elem == DiagramElem::Start) as u64).collect_vec();
 
-    for line in grid {
-        for (pos, element) in line.enumerate() {
+    for line in lines {
+        for (pos, element) in line.iter().enumerate() {
             // We encounter a splitter
-            if element == DiagramElem::Splitter {
+            if *element == DiagramElem::Splitter {
                 // Split the beams, does nothing if there are 0 superposed beams at position pos
                 superposed_beams[pos - 1] += superposed_beams[pos];
                 superposed_beams[pos + 1] += superposed_beams[pos];
@@ -82,6 +84,14 @@ mod tests {
         assert_eq!(result, Some(21));
     }
 
+    #[test]
+    fn test_ragged_lines() {
+        // Trailing spaces and short lines are empty space
+        let input = "..S..\n.....  \n..^\n";
+        assert_eq!(part_one(input), Some(1));
+        assert_eq!(part_two(input), Some(2));
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
@@ -1,54 +1,27 @@
-use std::{cmp::Ordering, sync::LazyLock};
-
-use advent_of_code::int_utils;
-use itertools::{Itertools, iproduct};
-use regex::Regex;
-use partial_sort::PartialSort;
-use disjoint::DisjointSet;
+use advent_of_code::input;
+use advent_of_code::spatial::KdTree;
+use advent_of_code::union_find::UnionFind;
 
 advent_of_code::solution!(8);
 
-static COORD_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+),(\d+),(\d+)").unwrap());
-
-fn parse_coords(input: &str) -> Vec<(u64, u64, u64)> {
-    COORD_RE.captures_iter(input)
-        .map(|capture| {
-            let (_, [x, y, z]) = capture.extract();
-            (x.parse().unwrap(), y.parse().unwrap(), z.parse().unwrap())
-        }).collect_vec()
-}
-
-fn distance_ordering(boxes: &[(u64, u64, u64)]) -> impl Fn(&(usize, usize), &(usize, usize)) -> Ordering {
-    |&(i, j), &(k, l)| int_utils::squared_eucl_3d(boxes[i], boxes[j]).cmp(&int_utils::squared_eucl_3d(boxes[k], boxes[l]))
+fn parse_coords(input: &str) -> Vec<[i64; 3]> {
+    input::tuples(input).unwrap()
 }
 
 fn part_one_aux(input: &str, num_pairs: usize) -> Option<u64> {
-    // Junction box list
-    let boxes = parse_coords(input);
-
-    // Box count
-    let num_boxes = boxes.len();
-
-    // Box index pairs ordered by distance
-    let mut box_pairs = iproduct!(0..num_boxes, 0..num_boxes).filter(|&(i, j)| i < j).collect_vec();
-    box_pairs.partial_sort(num_pairs, distance_ordering(&boxes));
+    // Junction box list, stored in a spatial index
+    let boxes = KdTree::new(parse_coords(input));
 
     // Union-find data structure representing circuits
-    let mut circuits = DisjointSet::with_len(num_boxes);
+    let mut circuits = UnionFind::new(boxes.points().len());
 
     // Link the closest box pairs together
-    for (i, j) in box_pairs.into_iter().take(num_pairs) {
-        circuits.join(i, j);
+    for (_, i, j) in boxes.closest_pairs().take(num_pairs) {
+        circuits.union(i, j);
     }
 
-    // Vec of circuit lengths
-    let mut circuit_lengths = circuits.sets().into_iter().map(|circuit| circuit.len() as u64).collect_vec();
-
-    // Find 3 largest circuits
-    circuit_lengths.partial_sort(3, |&x, &y| x.cmp(&y).reverse());
-
     // Return product of 3 largest circuits
-    Some(circuit_lengths.into_iter().take(3).product())
+    Some(circuits.largest_components(3).into_iter().map(|length| length as u64).product())
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
@@ -56,32 +29,28 @@ pub fn part_one(input: &str) -> Option<u64> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    // Junction box list
-    let boxes = parse_coords(input);
-
-    // Box count
-    let num_boxes = boxes.len();
-
-    // Box index pairs ordered by distance
-    let mut box_pairs = iproduct!(0..num_boxes, 0..num_boxes).filter(|&(i, j)| i < j).collect_vec();
-    box_pairs.sort_by(distance_ordering(&boxes));
+    // Junction box list, stored in a spatial index
+    let boxes = KdTree::new(parse_coords(input));
 
     // Last pair of boxes joined together
     let mut last_junction = None;
 
     // Union-find data structure representing circuits
-    let mut circuits = DisjointSet::with_len(num_boxes);
+    let mut circuits = UnionFind::new(boxes.points().len());
 
-    // Link the closest box pairs together
-    for (i, j) in box_pairs.into_iter() {
-        if circuits.root_of(i) != circuits.root_of(j) {
-            circuits.join(i, j);
-            last_junction = Some((i, j))
+    // Link the closest box pairs together until there is a single circuit left
+    for (_, i, j) in boxes.closest_pairs() {
+        if circuits.union(i, j) {
+            last_junction = Some((i, j));
+
+            if circuits.num_components() == 1 {
+                break;
+            }
         }
     }
 
     // Multiply x coordinates of last joined boxes
-    last_junction.map(|(i, j)| boxes[i].0 * boxes[j].0)
+    last_junction.map(|(i, j)| (boxes.points()[i][0] * boxes.points()[j][0]) as u64)
 }
 
 #[cfg(test)]
@@ -0,0 +1,250 @@
+/// Helpers for worksheet-like inputs, where values are laid out in groups of columns separated by blank columns.
+use std::any::type_name;
+use std::iter;
+use std::str::FromStr;
+
+use crate::input::ParseError;
+
+/// Returns the columns of the text, read from top to bottom. Shorter lines are padded with spaces.
+pub fn transpose<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let lines: Vec<Vec<char>> = lines
+        .into_iter()
+        .map(|line| line.chars().collect())
+        .collect();
+    let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+
+    (0..width)
+        .map(|column| {
+            lines
+                .iter()
+                .map(|line| line.get(column).copied().unwrap_or(' '))
+                .collect()
+        })
+        .collect()
+}
+
+/// Splits the text into groups of columns separated by columns that only contain spaces, in order. Shorter lines are
+/// padded with spaces.
+///
+/// ```text
+/// 123 328  51 64
+///  45 64  387 23
+///   6 98  215 314
+/// *   +   *   +
+/// ```
+///
+/// has 4 blocks, the first one being `"123"`, `" 45"`, `"  6"` and `"*  "`.
+pub fn column_blocks<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<ColumnBlock> {
+    let lines: Vec<Vec<char>> = lines
+        .into_iter()
+        .map(|line| line.chars().collect())
+        .collect();
+    let num_columns = lines.iter().map(Vec::len).max().unwrap_or(0);
+    let is_separator = |column: usize| {
+        lines
+            .iter()
+            .all(|line| line.get(column).is_none_or(|&c| c == ' '))
+    };
+
+    let mut blocks = vec![];
+    let mut column = 0;
+    while column < num_columns {
+        if is_separator(column) {
+            column += 1;
+            continue;
+        }
+
+        let start = column;
+        while column < num_columns && !is_separator(column) {
+            column += 1;
+        }
+        let width = column - start;
+
+        let mut text = String::with_capacity(lines.len() * (width + 1));
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                text.push('\n');
+            }
+            let row = &line[start.min(line.len())..column.min(line.len())];
+            text.extend(row);
+            text.extend(iter::repeat_n(' ', width - row.len()));
+        }
+        blocks.push(ColumnBlock {
+            start,
+            width,
+            num_rows: lines.len(),
+            text,
+        });
+    }
+
+    blocks
+}
+
+/// How the values of a [`ColumnBlock`] are aligned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    Left,
+    Right,
+    /// All the rows use the full width of the block
+    Both,
+}
+
+/// A group of adjacent columns of a text, see [`column_blocks`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ColumnBlock {
+    /// Index of the first column in the text
+    start: usize,
+    width: usize,
+    num_rows: usize,
+    /// Rows padded with spaces to the width of the block, separated by `\n`
+    text: String,
+}
+
+impl ColumnBlock {
+    /// Index of the first column of the block in the text.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Iterates over the rows, padded with spaces to the width of the block.
+    pub fn rows(&self) -> impl Iterator<Item = &str> {
+        self.text.split('\n').take(self.num_rows)
+    }
+
+    pub fn row(&self, index: usize) -> Option<&str> {
+        self.rows().nth(index)
+    }
+
+    /// Removes and returns the last row, useful when a worksheet ends with a row of operators.
+    pub fn pop_row(&mut self) -> Option<String> {
+        if self.num_rows == 0 {
+            return None;
+        }
+
+        self.num_rows -= 1;
+        let start = self.text.rfind('\n').unwrap_or(0);
+        let row = self.text.split_off(start);
+        Some(row.trim_start_matches('\n').to_string())
+    }
+
+    /// Returns the columns of the block, read from top to bottom.
+    pub fn columns(&self) -> Vec<String> {
+        transpose(self.rows())
+    }
+
+    /// Returns how the non-blank rows are aligned, `None` if they are neither left nor right-aligned.
+    pub fn alignment(&self) -> Option<Alignment> {
+        let rows = || self.rows().filter(|row| !row.trim().is_empty());
+        let left = rows().all(|row| !row.starts_with(' '));
+        let right = rows().all(|row| !row.ends_with(' '));
+
+        match (left, right) {
+            (true, true) => Some(Alignment::Both),
+            (true, false) => Some(Alignment::Left),
+            (false, true) => Some(Alignment::Right),
+            (false, false) => None,
+        }
+    }
+
+    /// Parses the value written on each row, from top to bottom. Blank rows are skipped, so both left-aligned and
+    /// right-aligned values are supported. Errors carry the 1-based row number.
+    pub fn horizontal_numbers<T: FromStr>(&self) -> Result<Vec<T>, ParseError> {
+        crate::input::parse_lines(&self.text)
+    }
+
+    /// Parses the value written in each column, read from top to bottom, from the leftmost column to the rightmost
+    /// one. Spaces are skipped, so digits don't need to be aligned. Errors carry the 1-based row number of the first
+    /// digit of the column.
+    pub fn vertical_numbers<T: FromStr>(&self) -> Result<Vec<T>, ParseError> {
+        let mut rows: Vec<_> = self.rows().map(str::chars).collect();
+        let mut value = String::new();
+        let mut ret = vec![];
+
+        for _ in 0..self.width {
+            value.clear();
+            let mut first_line = None;
+            for (i, row) in rows.iter_mut().enumerate() {
+                let c = row.next().unwrap_or(' ');
+                if c != ' ' {
+                    value.push(c);
+                    first_line.get_or_insert(i + 1);
+                }
+            }
+
+            if let Some(line) = first_line {
+                ret.push(value.parse().map_err(|_| {
+                    ParseError::new(line, &value, format!("a {}", type_name::<T>()))
+                })?);
+            }
+        }
+
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKSHEET: &str = "123 328  51 64\n 45 64  387 23\n  6 98  215 314\n*   +   *   +  \n";
+
+    #[test]
+    fn test_transpose() {
+        assert_eq!(transpose(["ab", "c", "def"]), ["acd", "b e", "  f"]);
+        assert!(transpose(Vec::<&str>::new()).is_empty());
+    }
+
+    #[test]
+    fn test_column_blocks() {
+        let blocks = column_blocks(WORKSHEET.lines());
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(
+            blocks.iter().map(ColumnBlock::start).collect::<Vec<_>>(),
+            [0, 4, 8, 12]
+        );
+        assert_eq!(
+            blocks[0].rows().collect::<Vec<_>>(),
+            ["123", " 45", "  6", "*  "]
+        );
+
+        // The last line is shorter than the others, it gets padded
+        assert_eq!(blocks[3].width(), 3);
+        assert_eq!(blocks[3].row(1), Some("23 "));
+        assert_eq!(column_blocks(["", "  "]), vec![]);
+    }
+
+    #[test]
+    fn test_numbers() {
+        let mut blocks = column_blocks(WORKSHEET.lines());
+        for block in &mut blocks {
+            block.pop_row();
+        }
+
+        // Right-aligned
+        assert_eq!(blocks[0].alignment(), Some(Alignment::Right));
+        assert_eq!(blocks[0].horizontal_numbers(), Ok(vec![123u32, 45, 6]));
+        assert_eq!(blocks[0].vertical_numbers(), Ok(vec![1u32, 24, 356]));
+
+        // Left-aligned
+        assert_eq!(blocks[1].alignment(), Some(Alignment::Left));
+        assert_eq!(blocks[1].horizontal_numbers(), Ok(vec![328u32, 64, 98]));
+        assert_eq!(blocks[1].vertical_numbers(), Ok(vec![369u32, 248, 8]));
+
+        assert_eq!(blocks[2].alignment(), Some(Alignment::Right));
+        assert_eq!(column_blocks(["1 2", " 3 "])[0].alignment(), None);
+        assert_eq!(blocks[3].vertical_numbers(), Ok(vec![623u32, 431, 4]));
+
+        let block = &column_blocks(["12", " x"])[0];
+        assert_eq!(block.horizontal_numbers::<u32>().unwrap_err().line, 2);
+        assert_eq!(block.vertical_numbers::<u32>().unwrap_err().line, 1);
+    }
+}
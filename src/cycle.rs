@@ -0,0 +1,251 @@
+/// Cycle detection in the sequence of states of a simulation, to skip to a far away step.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The cycle of a sequence `x0, f(x0), f(f(x0)), ...`: the state at step `start + length` is the same as the state at
+/// step `start`, and `start` and `length` are the smallest such values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// First step that is part of the cycle
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Returns the smallest step whose state is the same as the state at step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /// Returns the state at step `n`, running at most `start + length - 1` steps from `initial`.
+    pub fn extrapolate<T: Clone>(&self, initial: &T, mut step: impl FnMut(&T) -> T, n: usize) -> T {
+        let mut state = initial.clone();
+        for _ in 0..self.reduce(n) {
+            state = step(&state);
+        }
+        state
+    }
+}
+
+/// Finds the cycle of the sequence starting at `initial` with Floyd's tortoise and hare algorithm, in constant memory.
+///
+/// The sequence must end up cycling, which is always the case when there is a finite number of states, otherwise this
+/// never returns.
+pub fn floyd<T: Clone + PartialEq>(initial: &T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    // The hare goes twice as fast, they meet at a multiple of the cycle length
+    let mut tortoise = step(initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let middle = step(&hare);
+        hare = step(&middle);
+    }
+
+    // Restarting the tortoise, they meet again at the start of the cycle
+    let mut start = 0;
+    tortoise = initial.clone();
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Finds the cycle of the sequence starting at `initial` with Brent's algorithm, in constant memory. It usually calls
+/// `step` fewer times than [`floyd`].
+///
+/// The sequence must end up cycling, which is always the case when there is a finite number of states, otherwise this
+/// never returns.
+pub fn brent<T: Clone + PartialEq>(initial: &T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    // The tortoise teleports to the hare at every power of two, until the hare comes back to it
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // With the hare one cycle length ahead, they meet at the start of the cycle
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// A [`Cycle`] along with a value derived from each state of the sequence up to the end of the first iteration of the
+/// cycle, see [`find_hashed_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleValues<V> {
+    cycle: Cycle,
+    /// Value of each step in `0..start + length`
+    values: Vec<V>,
+}
+
+impl<V> CycleValues<V> {
+    pub fn cycle(&self) -> Cycle {
+        self.cycle
+    }
+
+    /// Values of the steps `0..start + length`, in order.
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Returns the value of the state at step `n`, without running any step.
+    pub fn extrapolate(&self, n: usize) -> &V {
+        &self.values[self.cycle.reduce(n)]
+    }
+}
+
+/// Finds the cycle of the sequence starting at `initial` by storing the states met in a [`HashMap`], so that each
+/// state is only compared with the ones that have the same hash. This is faster than [`floyd`] and [`brent`] when
+/// states are expensive to compare or to compute, such as grids, at the cost of memory.
+///
+/// `value` derives the value to keep for each step, e.g. a score, for [`CycleValues::extrapolate`].
+pub fn find_hashed_with<T: Hash + Eq, V>(
+    initial: T,
+    mut step: impl FnMut(&T) -> T,
+    mut value: impl FnMut(&T) -> V,
+) -> CycleValues<V> {
+    let mut seen = HashMap::new();
+    let mut values = vec![];
+    let mut state = initial;
+
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            let length = values.len() - start;
+            return CycleValues {
+                cycle: Cycle { start, length },
+                values,
+            };
+        }
+
+        values.push(value(&state));
+        let next = step(&state);
+        seen.insert(state, values.len() - 1);
+        state = next;
+    }
+}
+
+/// Same as [`find_hashed_with`], keeping the states themselves.
+pub fn find_hashed<T: Hash + Eq + Clone>(initial: T, step: impl FnMut(&T) -> T) -> CycleValues<T> {
+    find_hashed_with(initial, step, T::clone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Finds the cycle by remembering the first step of every state
+    fn brute_force(initial: u32, step: impl Fn(&u32) -> u32) -> Cycle {
+        let mut first_steps = HashMap::new();
+        let mut state = initial;
+        for n in 0.. {
+            if let Some(start) = first_steps.insert(state, n) {
+                return Cycle {
+                    start,
+                    length: n - start,
+                };
+            }
+            state = step(&state);
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        for modulus in [1, 2, 7, 100, 255, 1009] {
+            let step = |&x: &u32| (x * x + 1) % modulus;
+            for initial in 0..modulus.min(50) {
+                let expected = brute_force(initial, step);
+                assert_eq!(floyd(&initial, step), expected);
+                assert_eq!(brent(&initial, step), expected);
+                assert_eq!(find_hashed(initial, step).cycle(), expected);
+            }
+        }
+
+        // Pure cycle and fixed point
+        assert_eq!(
+            brent(&0, |&x| (x + 1) % 5),
+            Cycle {
+                start: 0,
+                length: 5
+            }
+        );
+        assert_eq!(
+            floyd(&9, |&x| x.min(3)),
+            Cycle {
+                start: 1,
+                length: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        let step = |&x: &u64| (x * x + 1) % 1009;
+        let brute = |n: usize| (0..n).fold(3, |x, _| step(&x));
+
+        let cycle = brent(&3, step);
+        let values = find_hashed(3, step);
+        assert_eq!(values.values().len(), cycle.start + cycle.length);
+        for n in [0, 1, 5, cycle.start, cycle.start + cycle.length, 1000, 4321] {
+            assert_eq!(cycle.extrapolate(&3, step, n), brute(n));
+            assert_eq!(*values.extrapolate(n), brute(n));
+        }
+        assert_eq!(
+            cycle.reduce(1_000_000_000),
+            cycle.reduce(1_000_000_000 - cycle.length)
+        );
+    }
+
+    #[test]
+    fn test_find_hashed_with() {
+        // Rotating a row of rocks, the score is the position of the first one
+        let rotate = |row: &Vec<char>| {
+            let mut row = row.clone();
+            row.rotate_right(1);
+            row
+        };
+        let row: Vec<char> = "#..#...".chars().collect();
+        let values = find_hashed_with(row, rotate, |row| row.iter().position(|&c| c == '#'));
+        assert_eq!(
+            values.cycle(),
+            Cycle {
+                start: 0,
+                length: 7
+            }
+        );
+        // 10^9 = 6 mod 7, the rocks are at 6 and 2
+        assert_eq!(values.extrapolate(1_000_000_000), &Some(2));
+    }
+}
@@ -0,0 +1,529 @@
+/// 2D and 3D integer geometry: points, vectors, distances and polygons.
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::int_utils::{self, Integer};
+
+/// A position in the plane. Subtracting two points gives the [`Vec2`] between them, which overflows with unsigned
+/// coordinates if the first point is not above and to the right of the second one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A displacement in the plane, that can be added to a [`Point2`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A position in space. Subtracting two points gives the [`Vec3`] between them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// A displacement in space, that can be added to a [`Point3`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// Implements the constructors, conversions and operators shared by a point type and its vector type.
+macro_rules! impl_point_vec {
+    ($point:ident, $vec:ident, $tuple:ty, $n:literal, $($field:ident),+) => {
+        impl<T: Integer> $point<T> {
+            pub const ORIGIN: Self = Self { $($field: T::ZERO),+ };
+
+            pub fn new($($field: T),+) -> Self {
+                Self { $($field),+ }
+            }
+
+            /// Manhattan (taxicab) distance, the sum of the absolute differences of the coordinates.
+            pub fn manhattan(self, other: Self) -> T::Unsigned {
+                T::Unsigned::ZERO $(+ self.$field.abs_diff(other.$field))+
+            }
+
+            /// Chebyshev distance, the largest absolute difference of the coordinates. It is the number of king moves
+            /// between two points.
+            pub fn chebyshev(self, other: Self) -> T::Unsigned {
+                T::Unsigned::ZERO $(.max(self.$field.abs_diff(other.$field)))+
+            }
+        }
+
+        impl<T: Integer> $vec<T> {
+            pub const ZERO: Self = Self { $($field: T::ZERO),+ };
+
+            pub fn new($($field: T),+) -> Self {
+                Self { $($field),+ }
+            }
+
+            pub fn dot(self, other: Self) -> T {
+                T::ZERO $(+ self.$field * other.$field)+
+            }
+
+            /// Sum of the absolute values of the coordinates.
+            pub fn manhattan_length(self) -> T::Unsigned {
+                T::Unsigned::ZERO $(+ self.$field.unsigned_abs())+
+            }
+        }
+
+        impl<T> From<$tuple> for $point<T> {
+            fn from(($($field),+): $tuple) -> Self {
+                Self { $($field),+ }
+            }
+        }
+
+        impl<T> From<[T; $n]> for $point<T> {
+            fn from([$($field),+]: [T; $n]) -> Self {
+                Self { $($field),+ }
+            }
+        }
+
+        impl<T> From<$tuple> for $vec<T> {
+            fn from(($($field),+): $tuple) -> Self {
+                Self { $($field),+ }
+            }
+        }
+
+        impl<T: Integer> Add<$vec<T>> for $point<T> {
+            type Output = Self;
+
+            fn add(self, rhs: $vec<T>) -> Self {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Integer> Sub<$vec<T>> for $point<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: $vec<T>) -> Self {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Integer> Sub for $point<T> {
+            type Output = $vec<T>;
+
+            fn sub(self, rhs: Self) -> $vec<T> {
+                $vec { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Integer> AddAssign<$vec<T>> for $point<T> {
+            fn add_assign(&mut self, rhs: $vec<T>) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Integer> SubAssign<$vec<T>> for $point<T> {
+            fn sub_assign(&mut self, rhs: $vec<T>) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<T: Integer> Add for $vec<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Integer> Sub for $vec<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Integer> Mul<T> for $vec<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl<T: Integer + Neg<Output = T>> Neg for $vec<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Integer> AddAssign for $vec<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Integer> SubAssign for $vec<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<T: Integer> MulAssign<T> for $vec<T> {
+            fn mul_assign(&mut self, rhs: T) {
+                *self = *self * rhs;
+            }
+        }
+    };
+}
+
+impl_point_vec!(Point2, Vec2, (T, T), 2, x, y);
+impl_point_vec!(Point3, Vec3, (T, T, T), 3, x, y, z);
+
+impl<T: Integer> Point2<T> {
+    /// Squared euclidean distance, returns `None` on overflow.
+    pub fn checked_squared_euclidean(self, other: Self) -> Option<T::Unsigned> {
+        let x_diff = self.x.abs_diff(other.x);
+        let y_diff = self.y.abs_diff(other.y);
+
+        x_diff.checked_pow(2)?.checked_add(y_diff.checked_pow(2)?)
+    }
+
+    /// Squared euclidean distance
+    ///
+    /// # Panics
+    ///
+    /// Panics on overflow, see [`Point2::checked_squared_euclidean`].
+    pub fn squared_euclidean(self, other: Self) -> T::Unsigned {
+        self.checked_squared_euclidean(other)
+            .expect("attempt to compute a distance with overflow")
+    }
+}
+
+impl<T: Integer> Point3<T> {
+    /// Squared euclidean distance, returns `None` on overflow.
+    pub fn checked_squared_euclidean(self, other: Self) -> Option<T::Unsigned> {
+        int_utils::checked_squared_eucl_3d(self.into(), other.into())
+    }
+
+    /// Squared euclidean distance
+    ///
+    /// # Panics
+    ///
+    /// Panics on overflow, see [`Point3::checked_squared_euclidean`].
+    pub fn squared_euclidean(self, other: Self) -> T::Unsigned {
+        int_utils::squared_eucl_3d(self.into(), other.into())
+    }
+}
+
+impl<T: Integer> Vec2<T> {
+    /// Z coordinate of the 3D cross product: positive if `other` is counterclockwise from `self`, negative if it is
+    /// clockwise, zero if they are collinear. It is computed in `i128` so that unsigned vectors can give a negative
+    /// result.
+    pub fn cross(self, other: Self) -> i128 {
+        self.x.to_i128() * other.y.to_i128() - self.y.to_i128() * other.x.to_i128()
+    }
+}
+
+impl<T: Integer> Vec3<T> {
+    pub fn cross(self, other: Self) -> Self {
+        Self {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(point: Point2<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl<T> From<Point3<T>> for (T, T, T) {
+    fn from(point: Point3<T>) -> Self {
+        (point.x, point.y, point.z)
+    }
+}
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/* --------------------------------- Polygons -------------------------------- */
+
+/// Where a point is relative to a polygon, see [`locate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+fn gcd<T: Integer>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Iterates over the edges of a polygon given by its vertices in order, the last vertex being linked to the first one.
+fn edges<T: Copy>(polygon: &[Point2<T>]) -> impl Iterator<Item = (Point2<T>, Point2<T>)> + '_ {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+/// Cross product of `a - origin` and `b - origin`, see [`Vec2::cross`]. The points are not subtracted in `T`, which
+/// would overflow with unsigned coordinates.
+fn cross<T: Integer>(origin: Point2<T>, a: Point2<T>, b: Point2<T>) -> i128 {
+    let (x, y) = (origin.x.to_i128(), origin.y.to_i128());
+    (a.x.to_i128() - x) * (b.y.to_i128() - y) - (a.y.to_i128() - y) * (b.x.to_i128() - x)
+}
+
+/// Twice the signed area of a polygon given by its vertices in order, with the shoelace formula. It is positive if the
+/// vertices are in counterclockwise order (with the y axis pointing up), negative otherwise. It is computed in `i128`,
+/// so that clockwise polygons with unsigned coordinates work too.
+pub fn signed_double_area<T: Integer>(polygon: &[Point2<T>]) -> i128 {
+    edges(polygon).fold(0, |acc, (a, b)| {
+        acc + (a.x.to_i128() * b.y.to_i128() - b.x.to_i128() * a.y.to_i128())
+    })
+}
+
+/// Twice the area of a polygon given by its vertices in order, in either direction. Doubling keeps the result an
+/// integer.
+pub fn double_area<T: Integer>(polygon: &[Point2<T>]) -> u128 {
+    signed_double_area(polygon).unsigned_abs()
+}
+
+/// Number of integer points on the boundary of a polygon with integer vertices.
+pub fn boundary_points<T: Integer>(polygon: &[Point2<T>]) -> T::Unsigned {
+    edges(polygon).fold(T::Unsigned::ZERO, |acc, (a, b)| {
+        acc + gcd(a.x.abs_diff(b.x), a.y.abs_diff(b.y))
+    })
+}
+
+/// Number of integer points strictly inside a simple polygon with integer vertices, with Pick's theorem
+/// `A = I + B / 2 - 1`. Degenerate polygons, with less than 3 vertices or all of them on a line, have no interior
+/// points.
+pub fn interior_points<T: Integer>(polygon: &[Point2<T>]) -> u128 {
+    let double_area = double_area(polygon);
+    if double_area == 0 {
+        // Pick's theorem doesn't apply, all the points are on the boundary
+        return 0;
+    }
+
+    let boundary_points = boundary_points(polygon).to_i128().unsigned_abs();
+    (double_area + 2 - boundary_points) / 2
+}
+
+/// Returns whether `point` is on the closed segment `[a, b]`.
+pub fn on_segment<T: Integer>(point: Point2<T>, a: Point2<T>, b: Point2<T>) -> bool {
+    cross(a, b, point) == 0
+        && point.x >= a.x.min(b.x)
+        && point.x <= a.x.max(b.x)
+        && point.y >= a.y.min(b.y)
+        && point.y <= a.y.max(b.y)
+}
+
+/// Locates `point` relative to a polygon given by its vertices in order, using its winding number so that
+/// self-intersecting polygons are supported.
+pub fn locate<T: Integer>(polygon: &[Point2<T>], point: Point2<T>) -> Location {
+    let mut winding = 0;
+
+    for (a, b) in edges(polygon) {
+        if on_segment(point, a, b) {
+            return Location::Boundary;
+        }
+
+        let side = cross(a, b, point);
+        if a.y <= point.y && b.y > point.y && side > 0 {
+            // Upward edge with the point on its left
+            winding += 1;
+        } else if a.y > point.y && b.y <= point.y && side < 0 {
+            // Downward edge with the point on its right
+            winding -= 1;
+        }
+    }
+
+    if winding == 0 {
+        Location::Outside
+    } else {
+        Location::Inside
+    }
+}
+
+/// Returns whether the closed segments `[a1, a2]` and `[b1, b2]` have at least one point in common, touching and
+/// overlapping collinear segments included.
+pub fn segments_intersect<T: Integer>(
+    (a1, a2): (Point2<T>, Point2<T>),
+    (b1, b2): (Point2<T>, Point2<T>),
+) -> bool {
+    let orientation = |p: Point2<T>, q: Point2<T>, r: Point2<T>| cross(p, q, r).cmp(&0);
+
+    let d1 = orientation(b1, b2, a1);
+    let d2 = orientation(b1, b2, a2);
+    let d3 = orientation(a1, a2, b1);
+    let d4 = orientation(a1, a2, b2);
+
+    if d1.is_ne() && d2.is_ne() && d3.is_ne() && d4.is_ne() {
+        return d1 != d2 && d3 != d4;
+    }
+
+    on_segment(a1, b1, b2)
+        || on_segment(a2, b1, b2)
+        || on_segment(b1, a1, a2)
+        || on_segment(b2, a1, a2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(vertices: &[(i64, i64)]) -> Vec<Point2<i64>> {
+        vertices.iter().map(|&vertex| vertex.into()).collect()
+    }
+
+    #[test]
+    fn test_operators() {
+        let mut p = Point2::new(1i32, 2);
+        let v = Vec2::new(3, -1);
+        assert_eq!(p + v, Point2::new(4, 1));
+        assert_eq!(p - v, Point2::new(-2, 3));
+        assert_eq!(Point2::new(4, 1) - p, v);
+        assert_eq!(v * 2 + v, Vec2::new(9, -3));
+        assert_eq!(-v - v, Vec2::new(-6, 2));
+        p += v;
+        p -= v * 2;
+        assert_eq!(p, Point2::new(-2, 3));
+        assert_eq!(p.to_string(), "(-2, 3)");
+
+        let q = Point3::new(1u64, 2, 3) + Vec3::new(1, 1, 1);
+        assert_eq!(q, Point3::from([2, 3, 4]));
+        assert_eq!(q - Point3::ORIGIN, Vec3::new(2, 3, 4));
+    }
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (Point2::new(-1i8, 5), Point2::new(3, 2));
+        assert_eq!(a.manhattan(b), 7u8);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(a.squared_euclidean(b), 25);
+        assert_eq!(
+            Point2::new(i8::MIN, 0).checked_squared_euclidean(Point2::new(i8::MAX, 0)),
+            None
+        );
+
+        let (a, b) = (Point3::new(162u64, 817, 812), Point3::new(425, 690, 689));
+        assert_eq!(a.squared_euclidean(b), 100427);
+        assert_eq!(a.manhattan(b), 263 + 127 + 123);
+        assert_eq!(a.chebyshev(b), 263);
+        assert_eq!((a - Point3::ORIGIN).manhattan_length(), 162 + 817 + 812);
+    }
+
+    #[test]
+    fn test_products() {
+        let (u, v) = (Vec2::new(1i32, 0), Vec2::new(0, 1));
+        assert_eq!(u.dot(v), 0);
+        assert_eq!(u.cross(v), 1);
+        assert_eq!(v.cross(u), -1);
+        assert_eq!(Vec2::new(0u8, 1).cross(Vec2::new(1, 0)), -1);
+
+        let (u, v) = (Vec3::new(1i64, 2, 3), Vec3::new(4, 5, 6));
+        assert_eq!(u.dot(v), 32);
+        assert_eq!(u.cross(v), Vec3::new(-3, 6, -3));
+        assert_eq!(u.cross(v).dot(u), 0);
+    }
+
+    #[test]
+    fn test_area() {
+        let square = polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]);
+        assert_eq!(signed_double_area(&square), 32);
+        assert_eq!(double_area(&square), 32);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+
+        let mut triangle = polygon(&[(0, 0), (5, 0), (0, 3)]);
+        triangle.reverse();
+        assert_eq!(signed_double_area(&triangle), -15);
+        assert_eq!(boundary_points(&triangle), 5 + 3 + 1);
+        assert_eq!(interior_points(&triangle), 4);
+        assert_eq!(double_area::<i64>(&[]), 0);
+
+        // Degenerate polygons
+        assert_eq!(interior_points::<i64>(&[]), 0);
+        assert_eq!(interior_points(&polygon(&[(2, 3)])), 0);
+        assert_eq!(interior_points(&polygon(&[(0, 0), (4, 0)])), 0);
+        assert_eq!(interior_points(&polygon(&[(0, 0), (2, 2), (4, 4)])), 0);
+    }
+
+    #[test]
+    fn test_unsigned_coordinates() {
+        // Clockwise, the differences of coordinates are negative
+        let square: Vec<Point2<u64>> = [(0, 0), (0, 4), (4, 4), (4, 0)].map(Point2::from).into();
+        assert_eq!(signed_double_area(&square), -32);
+        assert_eq!(double_area(&square), 32);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+
+        assert_eq!(locate(&square, Point2::new(1, 3)), Location::Inside);
+        assert_eq!(locate(&square, Point2::new(4, 2)), Location::Boundary);
+        assert_eq!(locate(&square, Point2::new(0, 5)), Location::Outside);
+        assert!(on_segment(
+            Point2::new(2, 2),
+            Point2::new(4, 4),
+            Point2::new(0, 0)
+        ));
+
+        let p = |x: u64, y: u64| Point2::new(x, y);
+        assert!(segments_intersect((p(4, 0), p(0, 4)), (p(0, 0), p(4, 4))));
+        assert!(!segments_intersect((p(5, 0), p(3, 0)), (p(2, 0), p(0, 0))));
+    }
+
+    #[test]
+    fn test_locate() {
+        // L-shaped polygon
+        let shape = polygon(&[(0, 0), (4, 0), (4, 2), (2, 2), (2, 4), (0, 4)]);
+        assert_eq!(locate(&shape, Point2::new(1, 1)), Location::Inside);
+        assert_eq!(locate(&shape, Point2::new(1, 3)), Location::Inside);
+        assert_eq!(locate(&shape, Point2::new(3, 3)), Location::Outside);
+        assert_eq!(locate(&shape, Point2::new(3, 2)), Location::Boundary);
+        assert_eq!(locate(&shape, Point2::new(0, 4)), Location::Boundary);
+        assert_eq!(locate(&shape, Point2::new(-1, 2)), Location::Outside);
+        assert_eq!(locate(&shape, Point2::new(5, 0)), Location::Outside);
+
+        // Pick's theorem agrees with counting the points
+        let inside = (0..=4)
+            .flat_map(|x| (0..=4).map(move |y| Point2::new(x, y)))
+            .filter(|&point| locate(&shape, point) == Location::Inside)
+            .count();
+        assert_eq!(inside as u128, interior_points(&shape));
+    }
+
+    #[test]
+    fn test_segments_intersect() {
+        let p = |x, y| Point2::new(x, y);
+        assert!(segments_intersect((p(0, 0), p(4, 4)), (p(0, 4), p(4, 0))));
+        assert!(!segments_intersect((p(0, 0), p(1, 1)), (p(0, 4), p(4, 0))));
+        // Touching at an end
+        assert!(segments_intersect((p(0, 0), p(2, 2)), (p(2, 2), p(4, 0))));
+        // Collinear, overlapping or not
+        assert!(segments_intersect((p(0, 0), p(3, 0)), (p(2, 0), p(5, 0))));
+        assert!(!segments_intersect((p(0, 0), p(1, 0)), (p(2, 0), p(5, 0))));
+        // Parallel
+        assert!(!segments_intersect((p(0, 0), p(3, 0)), (p(0, 1), p(3, 1))));
+    }
+}
@@ -0,0 +1,390 @@
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+use ndarray::{Array2, ArrayView1, ArrayViewMut1, Axis};
+
+use crate::iter_utils::{self, Neighborhood};
+
+/// A 2D grid of cells indexed by `(line, column)`, backed by an `ndarray::Array2`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Array2<T>,
+}
+
+impl<T> Grid<T> {
+    /// Wraps an existing array.
+    pub fn new(cells: Array2<T>) -> Self {
+        Self { cells }
+    }
+
+    /// Creates a grid of shape `(num_lines, num_columns)` where every cell is `elem`.
+    pub fn from_elem(shape: (usize, usize), elem: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(Array2::from_elem(shape, elem))
+    }
+
+    /// Creates a grid of shape `(num_lines, num_columns)` where cell `index` is `f(index)`.
+    pub fn from_shape_fn(shape: (usize, usize), f: impl FnMut((usize, usize)) -> T) -> Self {
+        Self::new(Array2::from_shape_fn(shape, f))
+    }
+
+    /// Parses a grid from puzzle text, mapping each character with `f`.
+    /// Empty lines are skipped, lines shorter than the longest one are padded with `f(' ')`.
+    pub fn parse(input: &str, f: impl FnMut(char) -> T) -> Self {
+        Self::from_lines(input.lines().filter(|line| !line.is_empty()), f)
+    }
+
+    /// Same as [`Grid::parse`], but takes the lines directly. Empty lines are kept.
+    pub fn from_lines<'a>(
+        lines: impl IntoIterator<Item = &'a str>,
+        mut f: impl FnMut(char) -> T,
+    ) -> Self {
+        let lines: Vec<&str> = lines.into_iter().collect();
+        let num_lines = lines.len();
+        let num_columns = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut cells = Vec::with_capacity(num_lines * num_columns);
+        for line in lines {
+            let len = cells.len();
+            cells.extend(line.chars().map(&mut f));
+            while cells.len() < len + num_columns {
+                cells.push(f(' '));
+            }
+        }
+
+        Self::new(Array2::from_shape_vec((num_lines, num_columns), cells).unwrap())
+    }
+
+    pub fn num_lines(&self) -> usize {
+        self.cells.len_of(Axis(0))
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.cells.len_of(Axis(1))
+    }
+
+    /// Returns `(num_lines, num_columns)`.
+    pub fn shape(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    /// Returns `true` if `index` is inside the grid.
+    pub fn contains_index(&self, (i, j): (usize, usize)) -> bool {
+        i < self.num_lines() && j < self.num_columns()
+    }
+
+    /// Returns the cell at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: (usize, usize)) -> Option<&T> {
+        self.cells.get(index)
+    }
+
+    /// Returns the cell at `index`, or `None` if it is out of bounds.
+    pub fn get_mut(&mut self, index: (usize, usize)) -> Option<&mut T> {
+        self.cells.get_mut(index)
+    }
+
+    /// Moves `index` by `(delta_line, delta_column)`, returns `None` if the result is out of bounds.
+    pub fn offset(
+        &self,
+        (i, j): (usize, usize),
+        (di, dj): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let index = (i.checked_add_signed(di)?, j.checked_add_signed(dj)?);
+        self.contains_index(index).then_some(index)
+    }
+
+    /// Returns the indices of the (up to 8) cells surrounding `index`.
+    pub fn neighbor_indices(
+        &self,
+        index: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        iter_utils::neighboring_indices(index, self.num_lines(), self.num_columns())
+    }
+
+    /// Returns the indices of the neighbors of `index` in `neighborhood`, neighbors outside the grid are skipped.
+    pub fn neighbors(
+        &self,
+        index: (usize, usize),
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        iter_utils::neighbors(index, self.num_lines(), self.num_columns(), neighborhood)
+    }
+
+    /// Same as [`Grid::neighbors`], also yields the direction `(delta_line, delta_column)` of each neighbor.
+    pub fn neighbors_with_direction(
+        &self,
+        index: (usize, usize),
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = ((usize, usize), (isize, isize))> + use<T> {
+        iter_utils::neighbors_with_direction(
+            index,
+            self.num_lines(),
+            self.num_columns(),
+            neighborhood,
+        )
+    }
+
+    /// Returns the indices of the neighbors of `index` in `neighborhood`, wrapping around the edges of the grid.
+    pub fn wrapping_neighbors(
+        &self,
+        index: (usize, usize),
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        iter_utils::wrapping_neighbors(index, self.num_lines(), self.num_columns(), neighborhood)
+    }
+
+    /// Returns the index of the first cell, in row-major order, matching `predicate`.
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        self.indexed_iter()
+            .find_map(|(index, cell)| predicate(cell).then_some(index))
+    }
+
+    /// Returns the indices of all cells, in row-major order, matching `predicate`.
+    pub fn positions(
+        &self,
+        mut predicate: impl FnMut(&T) -> bool,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        self.indexed_iter()
+            .filter_map(move |(index, cell)| predicate(cell).then_some(index))
+    }
+
+    /// Iterates over all cells in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Iterates over all cells in row-major order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Iterates over all cells and their indices in row-major order.
+    pub fn indexed_iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells.indexed_iter()
+    }
+
+    /// Iterates over all cells and their indices in row-major order.
+    pub fn indexed_iter_mut(&mut self) -> impl Iterator<Item = ((usize, usize), &mut T)> {
+        self.cells.indexed_iter_mut()
+    }
+
+    pub fn row(&self, i: usize) -> ArrayView1<'_, T> {
+        self.cells.row(i)
+    }
+
+    pub fn row_mut(&mut self, i: usize) -> ArrayViewMut1<'_, T> {
+        self.cells.row_mut(i)
+    }
+
+    pub fn column(&self, j: usize) -> ArrayView1<'_, T> {
+        self.cells.column(j)
+    }
+
+    pub fn column_mut(&mut self, j: usize) -> ArrayViewMut1<'_, T> {
+        self.cells.column_mut(j)
+    }
+
+    /// Iterates over the lines from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = ArrayView1<'_, T>> {
+        self.cells.rows().into_iter()
+    }
+
+    /// Iterates over the columns from left to right.
+    pub fn columns(&self) -> impl Iterator<Item = ArrayView1<'_, T>> {
+        self.cells.columns().into_iter()
+    }
+
+    /// Applies `f` to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.cells.map(f))
+    }
+
+    /// Returns the grid flipped along its main diagonal.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(self.cells.t().as_standard_layout().into_owned())
+    }
+
+    /// Returns the grid rotated by a quarter turn clockwise.
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let mut view = self.cells.t();
+        view.invert_axis(Axis(1));
+        Self::new(view.as_standard_layout().into_owned())
+    }
+
+    /// Returns the grid rotated by a quarter turn counterclockwise.
+    pub fn rotate_counterclockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let mut view = self.cells.t();
+        view.invert_axis(Axis(0));
+        Self::new(view.as_standard_layout().into_owned())
+    }
+
+    /// Returns the grid rotated by a half turn.
+    pub fn rotate_half(&self) -> Self
+    where
+        T: Clone,
+    {
+        let mut view = self.cells.view();
+        view.invert_axis(Axis(0));
+        view.invert_axis(Axis(1));
+        Self::new(view.as_standard_layout().into_owned())
+    }
+
+    /// Converts the grid back to text, one character per cell and one line per row.
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        let mut text = String::with_capacity(self.num_lines() * (self.num_columns() + 1));
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                text.push('\n');
+            }
+            text.extend(row.iter().map(&mut f));
+        }
+        text
+    }
+
+    pub fn as_array(&self) -> &Array2<T> {
+        &self.cells
+    }
+
+    pub fn as_array_mut(&mut self) -> &mut Array2<T> {
+        &mut self.cells
+    }
+
+    pub fn into_array(self) -> Array2<T> {
+        self.cells
+    }
+}
+
+impl<T> From<Array2<T>> for Grid<T> {
+    fn from(cells: Array2<T>) -> Self {
+        Self::new(cells)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &T {
+        &self.cells[index]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut T {
+        &mut self.cells[index]
+    }
+}
+
+/// Displays every cell next to each other, one line per row.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse("ab\ncd\nef\n", |c| c)
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = sample();
+        assert_eq!(grid.shape(), (3, 2));
+        assert_eq!(grid[(2, 1)], 'f');
+
+        let ragged = Grid::parse("abc\r\nd\n\nef", |c| c);
+        assert_eq!(ragged.shape(), (3, 3));
+        assert_eq!(ragged.to_string(), "abc\nd  \nef ");
+    }
+
+    #[test]
+    fn test_access() {
+        let grid = sample();
+        assert_eq!(grid.get((1, 1)), Some(&'d'));
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.offset((1, 1), (1, -1)), Some((2, 0)));
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((0, 1), (0, 1)), None);
+        assert_eq!(grid.neighbor_indices((0, 0)).count(), 3);
+        assert_eq!(
+            grid.neighbors((0, 0), Neighborhood::Orthogonal)
+                .collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+        assert_eq!(
+            grid.wrapping_neighbors((0, 0), Neighborhood::All).count(),
+            8
+        );
+    }
+
+    #[test]
+    fn test_position() {
+        let grid = sample();
+        assert_eq!(grid.position(|&c| c == 'e'), Some((2, 0)));
+        assert_eq!(grid.position(|&c| c == 'z'), None);
+        assert_eq!(
+            grid.positions(|&c| c > 'b').collect::<Vec<_>>(),
+            vec![(1, 0), (1, 1), (2, 0), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn test_rows_columns() {
+        let grid = sample();
+        assert_eq!(
+            grid.rows()
+                .map(|row| row.iter().collect::<String>())
+                .collect::<Vec<_>>(),
+            ["ab", "cd", "ef"]
+        );
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.iter().collect::<String>())
+                .collect::<Vec<_>>(),
+            ["ace", "bdf"]
+        );
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = sample();
+        assert_eq!(grid.transpose().to_string(), "ace\nbdf");
+        assert_eq!(grid.rotate_clockwise().to_string(), "eca\nfdb");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "bdf\nace");
+        assert_eq!(grid.rotate_half().to_string(), "fe\ndc\nba");
+        assert_eq!(grid.rotate_clockwise().rotate_counterclockwise(), grid);
+    }
+
+    #[test]
+    fn test_render() {
+        let grid = Grid::parse("@.\n.@", |c| c == '@');
+        assert_eq!(grid.render(|&roll| if roll { '@' } else { '.' }), "@.\n.@");
+        assert_eq!(grid.map(|&roll| u8::from(roll)).to_string(), "10\n01");
+    }
+}
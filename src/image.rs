@@ -0,0 +1,657 @@
+/// Exports grids as PPM and PNG images and animated GIFs, without any image crate.
+///
+/// Like the terminal visualizer, frames are only written when the `visualize` feature is enabled
+/// (`cargo solve <day> --visualize`) and the solution isn't benchmarked.
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::grid::Grid;
+use crate::template::Day;
+use crate::visualize;
+
+/// A color as `[red, green, blue]`.
+pub type Rgb = [u8; 3];
+
+#[derive(Debug)]
+pub enum ExportError {
+    IO(io::Error),
+    /// GIFs can't hold more than 256 colors.
+    TooManyColors,
+    /// GIFs can't be larger than 65535 pixels in either dimension.
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+    /// All frames of a GIF must have the same size.
+    FrameSizeMismatch,
+}
+
+impl From<io::Error> for ExportError {
+    fn from(e: io::Error) -> Self {
+        ExportError::IO(e)
+    }
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::IO(e) => write!(f, "could not write image: {e}"),
+            ExportError::TooManyColors => f.write_str("GIFs are limited to 256 colors"),
+            ExportError::TooLarge { width, height } => {
+                write!(
+                    f,
+                    "GIFs are limited to 65535x65535 pixels, got {width}x{height}"
+                )
+            }
+            ExportError::FrameSizeMismatch => {
+                f.write_str("all frames of a GIF must have the same size")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+/// An RGB image stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Draws every cell of `grid` as a `scale` by `scale` square colored by `palette`.
+    pub fn from_grid<T>(grid: &Grid<T>, scale: usize, mut palette: impl FnMut(&T) -> Rgb) -> Self {
+        let (num_lines, num_columns) = grid.shape();
+        let width = num_columns * scale;
+        let height = num_lines * scale;
+
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in grid.rows() {
+            let colors: Vec<Rgb> = row.iter().map(&mut palette).collect();
+            for _ in 0..scale {
+                for &color in &colors {
+                    pixels.extend(std::iter::repeat_n(color, scale));
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the color of the pixel at column `x` of row `y`.
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Encodes the image as a binary PPM (`P6`) file.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut data = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        data.extend(self.pixels.iter().flatten());
+        data
+    }
+
+    /// Encodes the image as a PNG file. The pixel data is stored without compression.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(self.height * (3 * self.width + 1));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            // Filter type 0: the row is stored as is
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, default compression, filtering and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_png_chunk(&mut png, b"IHDR", &header);
+        write_png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        write_png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn write_png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+    png.extend(crc32(kind.iter().chain(data)).to_be_bytes());
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Wraps `data` in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut zlib = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        let len = block.len() as u16;
+        zlib.push(u8::from(is_final));
+        zlib.extend(len.to_le_bytes());
+        zlib.extend((!len).to_le_bytes());
+        zlib.extend(block);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    zlib.extend(((b << 16) | a).to_be_bytes());
+    zlib
+}
+
+/// Encodes `frames` as a looping animated GIF, each frame staying on screen for `delay_centis` hundredths of a second.
+pub fn encode_gif(frames: &[Image], delay_centis: u16) -> Result<Vec<u8>, ExportError> {
+    let (width, height) = frames
+        .first()
+        .map_or((0, 0), |frame| (frame.width, frame.height));
+    if frames
+        .iter()
+        .any(|frame| (frame.width, frame.height) != (width, height))
+    {
+        return Err(ExportError::FrameSizeMismatch);
+    }
+    if width > u16::MAX as usize || height > u16::MAX as usize {
+        return Err(ExportError::TooLarge { width, height });
+    }
+
+    // Shared color table
+    let mut palette: Vec<Rgb> = vec![];
+    let mut color_indices: HashMap<Rgb, u8> = HashMap::new();
+    for &color in frames.iter().flat_map(|frame| &frame.pixels) {
+        if let Entry::Vacant(entry) = color_indices.entry(color) {
+            if palette.len() == 256 {
+                return Err(ExportError::TooManyColors);
+            }
+            entry.insert(palette.len() as u8);
+            palette.push(color);
+        }
+    }
+
+    // The color table holds 2^(size_bits + 1) colors, at least 4 so that the LZW code size is at least 2
+    let size_bits = palette.len().max(4).next_power_of_two().trailing_zeros() - 1;
+    palette.resize(1 << (size_bits + 1), [0, 0, 0]);
+
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend((width as u16).to_le_bytes());
+    gif.extend((height as u16).to_le_bytes());
+    gif.extend([0xF0 | size_bits as u8, 0, 0]);
+    gif.extend(palette.iter().flatten());
+
+    // Loop forever
+    gif.extend(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    for frame in frames {
+        // Graphic control extension holding the delay
+        gif.extend([0x21, 0xF9, 4, 0]);
+        gif.extend(delay_centis.to_le_bytes());
+        gif.extend([0, 0]);
+
+        // Image descriptor
+        gif.push(0x2C);
+        gif.extend([0, 0, 0, 0]);
+        gif.extend((width as u16).to_le_bytes());
+        gif.extend((height as u16).to_le_bytes());
+        gif.push(0);
+
+        let min_code_size = size_bits as u8 + 1;
+        let indices: Vec<u8> = frame
+            .pixels
+            .iter()
+            .map(|color| color_indices[color])
+            .collect();
+        gif.push(min_code_size);
+        for block in lzw_encode(&indices, min_code_size).chunks(255) {
+            gif.push(block.len() as u8);
+            gif.extend(block);
+        }
+        gif.push(0);
+    }
+
+    gif.push(0x3B);
+    Ok(gif)
+}
+
+/// Packs codes of varying width, least significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    output: Vec<u8>,
+    buffer: u32,
+    buffered_bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u8) {
+        self.buffer |= (code as u32) << self.buffered_bits;
+        self.buffered_bits += width;
+        while self.buffered_bits >= 8 {
+            self.output.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.buffered_bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.buffered_bits > 0 {
+            self.output.push(self.buffer as u8);
+        }
+        self.output
+    }
+}
+
+/// GIF flavored LZW: variable code width up to 12 bits, the dictionary is reset once full.
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    const MAX_CODE: u16 = 4095;
+
+    let clear_code = 1u16 << min_code_size;
+    let end_code = clear_code + 1;
+
+    let mut writer = BitWriter::default();
+    let mut width = min_code_size + 1;
+    let mut next_code = end_code + 1;
+    let mut dictionary: HashMap<(u16, u8), u16> = HashMap::new();
+    writer.write(clear_code, width);
+
+    if let Some((&first, rest)) = indices.split_first() {
+        let mut prefix = first as u16;
+        for &index in rest {
+            if let Some(&code) = dictionary.get(&(prefix, index)) {
+                prefix = code;
+                continue;
+            }
+
+            writer.write(prefix, width);
+            if next_code <= MAX_CODE {
+                dictionary.insert((prefix, index), next_code);
+                next_code += 1;
+                // Decoders add codes one step behind, so they only widen after reading the next code
+                if next_code > 1 << width && width < 12 {
+                    width += 1;
+                }
+            } else {
+                writer.write(clear_code, width);
+                dictionary.clear();
+                width = min_code_size + 1;
+                next_code = end_code + 1;
+            }
+            prefix = index as u16;
+        }
+        writer.write(prefix, width);
+    }
+
+    writer.write(end_code, width);
+    writer.finish()
+}
+
+/// Image format of the frames written by a [`FrameExporter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+}
+
+impl ImageFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+/// Writes successive states of a grid as numbered images, e.g. `frames/04/0000.png`, and optionally an animated GIF.
+///
+/// ```ignore
+/// let mut exporter = FrameExporter::for_day(DAY).scale(4).gif(5);
+/// automaton.run_until_fixpoint_with(|_, grid, _| {
+///     exporter.frame(grid, |&roll| if roll { [0, 200, 0] } else { [0, 0, 0] }).unwrap();
+/// });
+/// exporter.finish().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameExporter {
+    directory: PathBuf,
+    format: ImageFormat,
+    scale: usize,
+    frame: usize,
+    /// Frames of the GIF and delay between them, if one is written
+    gif: Option<(Vec<Image>, u16)>,
+    enabled: bool,
+}
+
+impl FrameExporter {
+    /// Writes PNG frames to `directory`, one pixel per cell.
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            format: ImageFormat::Png,
+            scale: 1,
+            frame: 0,
+            gif: None,
+            enabled: cfg!(feature = "visualize") && !visualize::is_benchmarking(),
+        }
+    }
+
+    /// Writes PNG frames to `frames/{day}`, one pixel per cell.
+    pub fn for_day(day: Day) -> Self {
+        Self::new(Path::new("frames").join(day.to_string()))
+    }
+
+    pub fn format(mut self, format: ImageFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Draws each cell as a `scale` by `scale` square.
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Also collects the frames into `animation.gif`, written by [`FrameExporter::finish`].
+    pub fn gif(mut self, delay_centis: u16) -> Self {
+        self.gif = Some((vec![], delay_centis));
+        self
+    }
+
+    /// Returns `false` if frames are not written.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Writes the next frame, returns its path or `None` if exporting is disabled.
+    pub fn frame<T>(
+        &mut self,
+        grid: &Grid<T>,
+        palette: impl FnMut(&T) -> Rgb,
+    ) -> Result<Option<PathBuf>, ExportError> {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        let image = Image::from_grid(grid, self.scale, palette);
+        let path = self
+            .directory
+            .join(format!("{:04}.{}", self.frame, self.format.extension()));
+        self.frame += 1;
+
+        fs::create_dir_all(&self.directory)?;
+        match self.format {
+            ImageFormat::Ppm => fs::write(&path, image.to_ppm())?,
+            ImageFormat::Png => fs::write(&path, image.to_png())?,
+        }
+
+        if let Some((frames, _)) = &mut self.gif {
+            frames.push(image);
+        }
+
+        Ok(Some(path))
+    }
+
+    /// Writes `animation.gif` if requested and returns its path.
+    pub fn finish(self) -> Result<Option<PathBuf>, ExportError> {
+        match self.gif {
+            Some((frames, delay_centis)) if self.enabled && !frames.is_empty() => {
+                let path = self.directory.join("animation.gif");
+                fs::write(&path, encode_gif(&frames, delay_centis)?)?;
+                Ok(Some(path))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Image {
+        let grid = Grid::parse("#.\n.#", |c| c == '#');
+        Image::from_grid(
+            &grid,
+            2,
+            |&wall| if wall { [255, 0, 0] } else { [0, 0, 255] },
+        )
+    }
+
+    #[test]
+    fn test_from_grid() {
+        let image = sample();
+        assert_eq!((image.width(), image.height()), (4, 4));
+        assert_eq!(image.pixel(1, 1), [255, 0, 0]);
+        assert_eq!(image.pixel(2, 1), [0, 0, 255]);
+        assert_eq!(image.pixel(3, 3), [255, 0, 0]);
+    }
+
+    #[test]
+    fn test_ppm() {
+        let ppm = sample().to_ppm();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+    }
+
+    #[test]
+    fn test_png() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+
+        let png = sample().to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0DIHDR"));
+        assert!(png.ends_with(b"\x00\x00\x00\x00IEND\xAE\x42\x60\x82"));
+        assert_eq!(zlib_stored(b"Wikipedia")[16..], [0x11, 0xE6, 0x03, 0x98]);
+    }
+
+    /// What a reference decoder read from the image data of a frame.
+    struct Decoded {
+        indices: Vec<u8>,
+        max_width: u8,
+        clears: usize,
+    }
+
+    /// Reference LZW decoder, reading codes as a GIF decoder does: the dictionary gets a new entry for every code but
+    /// the first one after a clear code, and codes widen once the next entry needs an extra bit.
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Decoded {
+        let clear_code = 1usize << min_code_size;
+        let end_code = clear_code + 1;
+        let initial_dictionary: Vec<Vec<u8>> = (0..=end_code)
+            .map(|code| {
+                if code < clear_code {
+                    vec![code as u8]
+                } else {
+                    vec![]
+                }
+            })
+            .collect();
+
+        let mut dictionary = initial_dictionary.clone();
+        let mut width = min_code_size + 1;
+        let mut previous: Option<usize> = None;
+        let mut decoded = Decoded {
+            indices: vec![],
+            max_width: width,
+            clears: 0,
+        };
+        let (mut buffer, mut buffered_bits, mut bytes) = (0u32, 0u8, data.iter());
+
+        loop {
+            while buffered_bits < width {
+                buffer |= (*bytes.next().expect("missing end code") as u32) << buffered_bits;
+                buffered_bits += 8;
+            }
+            let code = (buffer & ((1 << width) - 1)) as usize;
+            buffer >>= width;
+            buffered_bits -= width;
+
+            if code == clear_code {
+                dictionary = initial_dictionary.clone();
+                width = min_code_size + 1;
+                previous = None;
+                decoded.clears += 1;
+                continue;
+            }
+            if code == end_code {
+                assert!(bytes.next().is_none(), "data after the end code");
+                return decoded;
+            }
+
+            let entry = match (dictionary.get(code), previous) {
+                (Some(entry), _) => entry.clone(),
+                // The code being defined by this very step
+                (None, Some(previous)) if code == dictionary.len() => {
+                    let mut entry = dictionary[previous].clone();
+                    entry.push(entry[0]);
+                    entry
+                }
+                _ => panic!("code {code} read before being defined"),
+            };
+
+            if let Some(previous) = previous
+                && dictionary.len() < 4096
+            {
+                let mut new_entry = dictionary[previous].clone();
+                new_entry.push(entry[0]);
+                dictionary.push(new_entry);
+                if dictionary.len() == 1 << width && width < 12 {
+                    width += 1;
+                    decoded.max_width = decoded.max_width.max(width);
+                }
+            }
+
+            decoded.indices.extend(&entry);
+            previous = Some(code);
+        }
+    }
+
+    /// Decodes the frames of a GIF written by [`encode_gif`], along with what the LZW decoder saw.
+    fn decode_gif(gif: &[u8]) -> Vec<(Image, Decoded)> {
+        let width = u16::from_le_bytes([gif[6], gif[7]]) as usize;
+        let height = u16::from_le_bytes([gif[8], gif[9]]) as usize;
+        let palette_len = 2 << (gif[10] & 0x07);
+        let palette: Vec<Rgb> = gif[13..13 + 3 * palette_len]
+            .chunks(3)
+            .map(|color| [color[0], color[1], color[2]])
+            .collect();
+
+        // Skip the header, the color table and the loop extension
+        let mut position = 13 + 3 * palette_len + 19;
+        let mut frames = vec![];
+        while gif[position] != 0x3B {
+            // Skip the graphic control extension and the image descriptor
+            position += 8 + 10;
+            let min_code_size = gif[position];
+            position += 1;
+
+            let mut data = vec![];
+            while gif[position] != 0 {
+                let len = gif[position] as usize;
+                data.extend(&gif[position + 1..position + 1 + len]);
+                position += 1 + len;
+            }
+            position += 1;
+
+            let decoded = lzw_decode(&data, min_code_size);
+            let image = Image {
+                width,
+                height,
+                pixels: decoded
+                    .indices
+                    .iter()
+                    .map(|&i| palette[i as usize])
+                    .collect(),
+            };
+            frames.push((image, decoded));
+        }
+        frames
+    }
+
+    /// Pseudo-random image using `colors` colors.
+    fn noise(size: usize, colors: u64) -> Image {
+        let mut state = 12345u64;
+        let grid = Grid::from_shape_fn((size, size), |_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % colors
+        });
+        Image::from_grid(&grid, 1, |&c| [c as u8, (c * 7) as u8, 255 - c as u8])
+    }
+
+    #[test]
+    fn test_gif() {
+        let gif = encode_gif(&[sample(), sample()], 10).unwrap();
+        assert!(gif.starts_with(b"GIF89a\x04\x00\x04\x00\xF1"));
+        assert_eq!(gif.last(), Some(&0x3B));
+        for (image, decoded) in decode_gif(&gif) {
+            assert_eq!(image, sample());
+            assert_eq!(decoded.clears, 1);
+        }
+
+        let other = Image::from_grid(&Grid::from_elem((1, 1), ()), 1, |_| [0, 0, 0]);
+        assert!(matches!(
+            encode_gif(&[sample(), other], 10),
+            Err(ExportError::FrameSizeMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_gif_lzw_round_trip() {
+        // Noise fills the dictionary up to 12-bit codes and clears it, a single color exercises codes used as soon as
+        // they are defined
+        let scaled = Image::from_grid(&Grid::parse("#.\n.#", |c| c == '#'), 64, |&wall| {
+            if wall { [3, 21, 252] } else { [0, 0, 255] }
+        });
+        let flat = Image::from_grid(&Grid::from_elem((128, 128), ()), 1, |_| [0, 0, 255]);
+        for colors in [16, 256] {
+            let frames = [noise(128, colors), flat.clone(), scaled.clone()];
+            let gif = encode_gif(&frames, 5).unwrap();
+
+            let decoded = decode_gif(&gif);
+            assert_eq!(decoded.len(), frames.len());
+            for ((image, _), frame) in decoded.iter().zip(&frames) {
+                assert_eq!(image, frame);
+            }
+
+            let (_, noise) = &decoded[0];
+            assert_eq!(noise.max_width, 12);
+            assert!(noise.clears > 1);
+        }
+    }
+}
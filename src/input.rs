@@ -0,0 +1,514 @@
+/// Helpers to parse puzzle inputs: lines, paragraphs, integers, ranges and tuples.
+///
+/// Errors carry the 1-based line number they happened on. When parsing a paragraph, line numbers are relative to it.
+use std::any::type_name;
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::interval_set::parse_range;
+
+/// An error which can be returned by the parsing helpers of this module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number
+    pub line: usize,
+    /// The part of the line that couldn't be parsed
+    pub content: String,
+    /// Description of what was expected
+    pub expected: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(line: usize, content: &str, expected: impl Into<String>) -> Self {
+        Self {
+            line,
+            content: content.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Sets the line number, for errors coming from a single line or from a paragraph.
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: expecting {}, got \"{}\"",
+            self.line, self.expected, self.content
+        )
+    }
+}
+
+/// Iterates over the lines that aren't empty or only made of whitespace, with their 1-based line number.
+pub fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, line))
+}
+
+/// Iterates over the lines that aren't empty or only made of whitespace.
+pub fn non_empty_lines(input: &str) -> impl Iterator<Item = &str> {
+    numbered_lines(input).map(|(_, line)| line)
+}
+
+/// Iterates over the blocks of lines separated by one or more blank lines. Each paragraph keeps its line breaks, but
+/// not the trailing one.
+pub fn paragraphs(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+
+    std::iter::from_fn(move || {
+        // Skip the leading blank lines
+        while let Some((line, tail)) = rest.split_once('\n')
+            && line.trim().is_empty()
+        {
+            rest = tail;
+        }
+        if rest.trim().is_empty() {
+            return None;
+        }
+
+        // The paragraph ends at the first blank line
+        let mut end = rest.len();
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim().is_empty() {
+                end = offset;
+                break;
+            }
+            offset += line.len();
+        }
+
+        let paragraph = &rest[..end];
+        rest = &rest[end..];
+        Some(paragraph.trim_end_matches(['\n', '\r']))
+    })
+}
+
+/// Parses every non-empty line with [`FromStr`].
+pub fn parse_lines<T: FromStr>(input: &str) -> Result<Vec<T>, ParseError> {
+    numbered_lines(input)
+        .map(|(i, line)| {
+            line.trim()
+                .parse()
+                .map_err(|_| ParseError::new(i, line, format!("a {}", type_name::<T>())))
+        })
+        .collect()
+}
+
+/// Extracts every integer of the input, with minus signs if `signed`, see [`signed_integers`].
+fn integers<T: FromStr>(input: &str, signed: bool) -> Result<Vec<T>, ParseError> {
+    let mut ret = vec![];
+
+    for (i, line) in numbered_lines(input) {
+        let bytes = line.as_bytes();
+        let mut start = 0;
+
+        while start < bytes.len() {
+            if !bytes[start].is_ascii_digit() {
+                start += 1;
+                continue;
+            }
+
+            let mut end = start;
+            while end < bytes.len() && bytes[end].is_ascii_digit() {
+                end += 1;
+            }
+
+            let is_negative = signed
+                && start > 0
+                && bytes[start - 1] == b'-'
+                && (start == 1 || !bytes[start - 2].is_ascii_alphanumeric());
+            let token = &line[start - is_negative as usize..end];
+
+            ret.push(token.parse().map_err(|_| {
+                ParseError::new(
+                    i,
+                    token,
+                    format!("an integer fitting in a {}", type_name::<T>()),
+                )
+            })?);
+            start = end;
+        }
+    }
+
+    Ok(ret)
+}
+
+/// Extracts every unsigned integer of the input, ignoring the other characters, `-` included.
+pub fn unsigned_integers<T: FromStr>(input: &str) -> Result<Vec<T>, ParseError> {
+    integers(input, false)
+}
+
+/// Extracts every integer of the input, ignoring the other characters. A `-` right before a number is a minus sign,
+/// unless it follows a letter or a digit: `3-5` gives `3` and `5`, but `x=-5` gives `-5`.
+pub fn signed_integers<T: FromStr>(input: &str) -> Result<Vec<T>, ParseError> {
+    integers(input, true)
+}
+
+/// Parses ranges written `a-b`, separated by commas or whitespace, including line breaks.
+pub fn ranges(input: &str) -> Result<Vec<RangeInclusive<u64>>, ParseError> {
+    let mut ret = vec![];
+
+    for (i, line) in numbered_lines(input) {
+        for token in line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+        {
+            let range = parse_range(token)
+                .ok_or_else(|| ParseError::new(i, token, "a range `a-b` with a <= b"))?;
+            ret.push(range);
+        }
+    }
+
+    Ok(ret)
+}
+
+/// Parses one tuple of `N` comma-separated values per non-empty line, like `x,y,z`. Spaces around values are allowed.
+pub fn tuples<const N: usize, T: FromStr>(input: &str) -> Result<Vec<[T; N]>, ParseError> {
+    numbered_lines(input)
+        .map(|(i, line)| {
+            let error =
+                || ParseError::new(i, line, format!("{N} comma-separated {}", type_name::<T>()));
+            let values: Vec<T> = line
+                .split(',')
+                .map(|value| value.trim().parse().map_err(|_| error()))
+                .collect::<Result<_, _>>()?;
+            values.try_into().map_err(|_| error())
+        })
+        .collect()
+}
+
+/// A piece of a [`scan!`](crate::scan) format string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment<'a> {
+    Literal(&'a str),
+    /// `{char}`, exactly one character
+    Char,
+    /// `{u32}`, `{i64}`, ..., as many digits as possible, with a sign if `signed`
+    Integer {
+        signed: bool,
+    },
+    /// `{}` or any other type, everything up to the next literal
+    Any,
+}
+
+/// Splits a format string into literals and placeholders, `{{` and `}}` being escaped braces.
+fn parse_format(format: &str) -> Vec<Segment<'_>> {
+    let mut segments = vec![];
+    let mut rest = format;
+
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix("{{") {
+            segments.push(Segment::Literal("{"));
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("}}") {
+            segments.push(Segment::Literal("}"));
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix('{') {
+            let (name, tail) = tail
+                .split_once('}')
+                .unwrap_or_else(|| panic!("unclosed placeholder in format \"{format}\""));
+            segments.push(match name.trim() {
+                "char" => Segment::Char,
+                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => {
+                    Segment::Integer { signed: false }
+                }
+                "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => {
+                    Segment::Integer { signed: true }
+                }
+                _ => Segment::Any,
+            });
+            rest = tail;
+        } else {
+            let end = rest.find(['{', '}']).unwrap_or(rest.len()).max(1);
+            segments.push(Segment::Literal(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+
+    segments
+}
+
+/// Splits `line` into the fields of the [`scan!`](crate::scan) `format`, trailing whitespace excluded.
+///
+/// # Panics
+///
+/// Panics if the format doesn't have `num_fields` placeholders, or if a `{}` is directly followed by another
+/// placeholder, since the split would be ambiguous.
+#[doc(hidden)]
+pub fn scan_fields<'a>(
+    line: &'a str,
+    format: &str,
+    num_fields: usize,
+) -> Result<Vec<&'a str>, ParseError> {
+    let segments = parse_format(format);
+    let placeholders = segments
+        .iter()
+        .filter(|segment| !matches!(segment, Segment::Literal(_)))
+        .count();
+    assert_eq!(
+        placeholders, num_fields,
+        "format \"{format}\" has {placeholders} placeholders but {num_fields} types were given"
+    );
+
+    let mut fields = vec![];
+    let trimmed = line.trim_end();
+    let mut rest = trimmed;
+    let error = |rest: &str, expected: String| {
+        let column = trimmed.len() - rest.len() + 1;
+        ParseError::new(
+            1,
+            line,
+            format!("{expected} at column {column} (format \"{format}\")"),
+        )
+    };
+
+    for (i, segment) in segments.iter().enumerate() {
+        let len = match *segment {
+            Segment::Literal(literal) => {
+                rest = rest
+                    .strip_prefix(literal)
+                    .ok_or_else(|| error(rest, format!("\"{literal}\"")))?;
+                continue;
+            }
+            Segment::Char => rest
+                .chars()
+                .next()
+                .map(char::len_utf8)
+                .ok_or_else(|| error(rest, "a character".to_string()))?,
+            Segment::Integer { signed } => {
+                let sign = (signed && rest.starts_with(['-', '+'])) as usize;
+                let digits = rest[sign..].bytes().take_while(u8::is_ascii_digit).count();
+                if digits == 0 {
+                    return Err(error(rest, "an integer".to_string()));
+                }
+                sign + digits
+            }
+            Segment::Any => match segments.get(i + 1) {
+                None => rest.len(),
+                Some(Segment::Literal(literal)) => rest
+                    .find(literal)
+                    .ok_or_else(|| error(rest, format!("a field followed by \"{literal}\"")))?,
+                Some(_) => {
+                    panic!("ambiguous format \"{format}\": `{{}}` followed by a placeholder")
+                }
+            },
+        };
+
+        let (field, tail) = rest.split_at(len);
+        fields.push(field);
+        rest = tail;
+    }
+
+    if !rest.is_empty() {
+        return Err(error(rest, "the end of the line".to_string()));
+    }
+
+    Ok(fields)
+}
+
+/// Parses a field returned by [`scan_fields`].
+#[doc(hidden)]
+pub fn parse_field<T: FromStr>(
+    line: &str,
+    format: &str,
+    index: usize,
+    field: &str,
+) -> Result<T, ParseError> {
+    field.parse().map_err(|_| {
+        ParseError::new(
+            1,
+            line,
+            format!(
+                "field {} \"{field}\" to be a {} (format \"{format}\")",
+                index + 1,
+                type_name::<T>()
+            ),
+        )
+    })
+}
+
+/// Parses a line into a tuple, following a format string where `{}` placeholders stand for the fields.
+///
+/// `{}` matches everything up to the next literal part of the format. Placeholders can also name a type to match
+/// fields that aren't separated: `{char}` matches a single character, and integer types like `{u32}` or `{i64}` match
+/// as many digits as possible, with a sign for signed types. `{{` and `}}` are escaped braces.
+///
+/// `scan!(line, format, (types...))` returns a `Result<(types...), ParseError>`, and
+/// `scan!(each input, format, (types...))` returns an iterator over the results for every non-empty line of `input`.
+///
+/// ```ignore
+/// let (from, to) = scan!("123-456", "{}-{}", (u64, u64))?;
+/// let rotations: Vec<(char, u32)> = scan!(each input, "{char}{u32}", (char, u32)).collect::<Result<_, _>>()?;
+/// ```
+#[macro_export]
+macro_rules! scan {
+    (each $input:expr, $format:expr, ($($t:ty),+ $(,)?)) => {
+        $crate::input::numbered_lines($input).map(|(number, line)| {
+            $crate::scan!(line, $format, ($($t),+)).map_err(|error| error.at_line(number))
+        })
+    };
+
+    ($line:expr, $format:expr, ($($t:ty),+ $(,)?)) => {
+        (|| -> ::std::result::Result<($($t,)+), $crate::input::ParseError> {
+            let line: &str = $line;
+            let format: &str = $format;
+            let num_fields = [$(stringify!($t)),+].len();
+            let mut fields = $crate::input::scan_fields(line, format, num_fields)?.into_iter().enumerate();
+
+            Ok(($({
+                let (index, field) = fields.next().unwrap();
+                $crate::input::parse_field::<$t>(line, format, index, field)?
+            },)+))
+        })()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let input = "a\r\n\n  \nb c\n\nd\n";
+        assert_eq!(
+            non_empty_lines(input).collect::<Vec<_>>(),
+            ["a", "b c", "d"]
+        );
+        assert_eq!(
+            numbered_lines(input).collect::<Vec<_>>(),
+            [(1, "a"), (4, "b c"), (6, "d")]
+        );
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let input = "\n1\n2\n\n\n3\n \n4\r\n5\r\n";
+        assert_eq!(
+            paragraphs(input).collect::<Vec<_>>(),
+            ["1\n2", "3", "4\r\n5"]
+        );
+        assert_eq!(paragraphs("").count(), 0);
+        assert_eq!(paragraphs("\n\n").count(), 0);
+        assert_eq!(paragraphs("1").collect::<Vec<_>>(), ["1"]);
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        assert_eq!(parse_lines::<u32>("1\n\n 2\n3\n"), Ok(vec![1, 2, 3]));
+
+        let error = parse_lines::<u32>("1\n\nx\n").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.content, "x");
+    }
+
+    #[test]
+    fn test_integers() {
+        let input = "p=0,4 v=3,-3\nrange 3-5, x=-12\n";
+        assert_eq!(
+            unsigned_integers::<u32>(input),
+            Ok(vec![0, 4, 3, 3, 3, 5, 12])
+        );
+        assert_eq!(
+            signed_integers::<i64>(input),
+            Ok(vec![0, 4, 3, -3, 3, 5, -12])
+        );
+        assert_eq!(signed_integers::<i32>("-7"), Ok(vec![-7]));
+
+        let error = unsigned_integers::<u8>("1 2\n3 256").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.content, "256");
+        assert!(
+            error
+                .to_string()
+                .starts_with("line 2: expecting an integer fitting in a u8")
+        );
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(
+            ranges("11-22,95-115,\n998-1012\n"),
+            Ok(vec![11..=22, 95..=115, 998..=1012])
+        );
+        assert_eq!(ranges("1-2\n\n3-x").unwrap_err().line, 3);
+        assert_eq!(ranges("5-3").unwrap_err().content, "5-3");
+    }
+
+    #[test]
+    fn test_tuples() {
+        assert_eq!(
+            tuples::<3, i64>("1,2,3\n-4, 5 ,6\n"),
+            Ok(vec![[1, 2, 3], [-4, 5, 6]])
+        );
+        assert_eq!(tuples::<2, u32>("1,2\n1,2,3").unwrap_err().line, 2);
+        assert_eq!(tuples::<2, u32>("1,x").unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn test_scan() {
+        assert_eq!(crate::scan!("123-456", "{}-{}", (u64, u64)), Ok((123, 456)));
+        assert_eq!(
+            crate::scan!("L68", "{char}{u32}", (char, u32)),
+            Ok(('L', 68))
+        );
+        assert_eq!(
+            crate::scan!("x=-3..+5", "x={i32}..{i32}", (i32, i32)),
+            Ok((-3, 5))
+        );
+        assert_eq!(
+            crate::scan!(
+                "move 3 from a to {b}\r",
+                "move {} from {} to {{{}}}",
+                (u8, String, char)
+            ),
+            Ok((3, "a".to_string(), 'b'))
+        );
+        assert_eq!(crate::scan!("42", "{}", (u32)), Ok((42,)));
+
+        let error = crate::scan!("123+456", "{}-{}", (u64, u64)).unwrap_err();
+        assert_eq!(error.content, "123+456");
+        assert_eq!(
+            error.expected,
+            "a field followed by \"-\" at column 1 (format \"{}-{}\")"
+        );
+
+        let error = crate::scan!("12-x", "{}-{}", (u64, u64)).unwrap_err();
+        assert_eq!(
+            error.expected,
+            "field 2 \"x\" to be a u64 (format \"{}-{}\")"
+        );
+
+        let error = crate::scan!("L68 ", "{char}", (char)).unwrap_err();
+        assert_eq!(
+            error.expected,
+            "the end of the line at column 2 (format \"{char}\")"
+        );
+        assert!(crate::scan!("R", "{char}{u32}", (char, u32)).is_err());
+    }
+
+    #[test]
+    fn test_scan_each() {
+        let input = "L68\n\nR5\nX\n";
+        let results: Vec<_> = crate::scan!(each input, "{char}{u32}", (char, u32)).collect();
+        assert_eq!(results[..2], [Ok(('L', 68)), Ok(('R', 5))]);
+        assert_eq!(results[2].as_ref().unwrap_err().line, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "has 2 placeholders but 1 types were given")]
+    fn test_scan_wrong_arity() {
+        let _ = crate::scan!("1-2", "{}-{}", (u32));
+    }
+}
@@ -1,17 +1,107 @@
-use std::iter;
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Rem, Sub};
 
 
-/// Returns the number of digits of `n`.
-pub fn num_digits(n: u64) -> u32 {
-    n.checked_ilog10().unwrap_or(0) + 1
+/// Primitive integer, signed or unsigned, that the functions of this module are generic over.
+pub trait Integer:
+    Copy + Ord + Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Rem<Output = Self>
+{
+    /// Unsigned integer of the same width, used for absolute values and distances
+    type Unsigned: Integer;
+
+    const ZERO: Self;
+    const ONE: Self;
+    const TEN: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+    fn unsigned_abs(self) -> Self::Unsigned;
+    fn abs_diff(self, other: Self) -> Self::Unsigned;
+    fn ilog10_or_zero(self) -> u32;
+
+    /// Converts to `i128`, to compute signed values such as differences whatever the signedness of `Self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `u128` doesn't fit in an `i128`.
+    fn to_i128(self) -> i128;
+}
+
+macro_rules! impl_integer {
+    ($($signed:ty => $unsigned:ty),*) => {
+        $(
+            impl_integer!(@impl $unsigned, $unsigned, self, self);
+            impl_integer!(@impl $signed, $unsigned, self, self.unsigned_abs());
+        )*
+    };
+    (@impl $t:ty, $u:ty, $self:ident, $abs:expr) => {
+        impl Integer for $t {
+            type Unsigned = $u;
+
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const TEN: Self = 10;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_pow(self, exp: u32) -> Option<Self> {
+                <$t>::checked_pow(self, exp)
+            }
+
+            fn unsigned_abs($self) -> $u {
+                $abs
+            }
+
+            fn abs_diff(self, other: Self) -> $u {
+                <$t>::abs_diff(self, other)
+            }
+
+            fn ilog10_or_zero(self) -> u32 {
+                self.checked_ilog10().unwrap_or(0)
+            }
+
+            fn to_i128(self) -> i128 {
+                i128::try_from(self).expect("attempt to convert to i128 with overflow")
+            }
+        }
+    };
+}
+
+impl_integer!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+/// Sums and products of iterators that return `None` on overflow, instead of panicking in debug builds and wrapping
+/// around in release builds like [`Iterator::sum`] and [`Iterator::product`].
+pub trait CheckedAccumulate<T: Integer>: Iterator<Item = T> + Sized {
+    fn checked_sum(mut self) -> Option<T> {
+        self.try_fold(T::ZERO, T::checked_add)
+    }
+
+    fn checked_product(mut self) -> Option<T> {
+        self.try_fold(T::ONE, T::checked_mul)
+    }
+}
+
+impl<T: Integer, I: Iterator<Item = T>> CheckedAccumulate<T> for I {}
+
+/// Returns the number of digits of `n`, the minus sign is not counted.
+pub fn num_digits<T: Integer>(n: T) -> u32 {
+    n.unsigned_abs().ilog10_or_zero() + 1
 }
 
-/// Splits `n` in half, returns `None` if its number of digits is even.
-pub fn split_in_half(n: u64) -> Option<(u64, u64)> {
+/// Splits `n` in half, returns `None` if its number of digits is even. Both halves have the sign of `n`.
+pub fn split_in_half<T: Integer>(n: T) -> Option<(T, T)> {
     let len = num_digits(n);
 
     if len.is_multiple_of(2) {
-        let divisor = 10u64.pow(len / 2);
+        // 10^(len/2) is at most n, it can't overflow
+        let divisor = T::TEN.checked_pow(len / 2).unwrap();
         Some((n / divisor, n % divisor))
     } else {
         None
@@ -19,13 +109,16 @@ pub fn split_in_half(n: u64) -> Option<(u64, u64)> {
 }
 
 /// Splits `number` is `num_parts` parts, returns `None` if the number of digits of `number` is not a multiple of `num_parts`.
-/// The returned list is little-endian.
-pub fn split_in_parts(number: u64, num_parts: u32) -> Option<Vec<u64>> {
+/// The returned list is little-endian, every part has the sign of `number`.
+pub fn split_in_parts<T: Integer>(number: T, num_parts: u32) -> Option<Vec<T>> {
     let len = num_digits(number);
     let mut ret = vec![];
 
     if len.is_multiple_of(num_parts) {
-        let divisor = 10u64.pow(len / num_parts);
+        // 10^len may not fit in T when there is a single part
+        let Some(divisor) = T::TEN.checked_pow(len / num_parts) else {
+            return Some(vec![number]);
+        };
         let mut dividend = number;
 
         for _ in 0 .. num_parts {
@@ -40,106 +133,311 @@ pub fn split_in_parts(number: u64, num_parts: u32) -> Option<Vec<u64>> {
     }
 }
 
-/// Concats two integers together.
-pub fn concat(a: u64, b: u64) -> u64 {
-    a * 10u64.pow(num_digits(b)) + b
+/// Concats two integers together, they should have the same sign. Returns `None` on overflow.
+pub fn checked_concat<T: Integer>(a: T, b: T) -> Option<T> {
+    a.checked_mul(T::TEN.checked_pow(num_digits(b))?)?.checked_add(b)
+}
+
+/// Concats two integers together, they should have the same sign.
+///
+/// # Panics
+///
+/// Panics on overflow, see [`checked_concat`].
+pub fn concat<T: Integer>(a: T, b: T) -> T {
+    checked_concat(a, b).expect("attempt to concat with overflow")
+}
+
+/// Concats a little-endian list of integers together, returns None if `list.len()` is `0` or on overflow.
+pub fn checked_concat_list<T: Integer>(list: &[T]) -> Option<T> {
+    let (&first, rest) = list.split_first()?;
+    rest.iter().try_fold(first, |acc, &elem| checked_concat(elem, acc))
 }
 
 /// Concats a little-endian list of integers together, returns None if `list.len()` is `0`.
-pub fn concat_list(list: &[u64]) -> Option<u64> {
+///
+/// # Panics
+///
+/// Panics on overflow, see [`checked_concat_list`].
+pub fn concat_list<T: Integer>(list: &[T]) -> Option<T> {
     list.iter().copied().reduce(|acc, elem| concat(elem, acc))
 }
 
-/// Repeats a `number` `repetitions` times, should be faster than concat_list
-pub fn repeat(number: u64, repetitions: u32) -> Option<u64> {
-    iter::repeat_n(number, repetitions as usize).reduce(|acc, elem| concat(elem, acc))
+/// Repeats a `number` `repetitions` times, returns `None` if `repetitions` is `0` or on overflow.
+pub fn checked_repeat<T: Integer>(number: T, repetitions: u32) -> Option<T> {
+    if repetitions == 0 {
+        return None;
+    }
+
+    // Multiplier of the last repetition, e.g. 100 for a 2-digit number
+    let shift = T::TEN.checked_pow(num_digits(number))?;
+    let mut ret = number;
+
+    for _ in 1 .. repetitions {
+        ret = ret.checked_mul(shift)?.checked_add(number)?;
+    }
+
+    Some(ret)
+}
+
+/// Repeats a `number` `repetitions` times, should be faster than concat_list. Returns `None` if `repetitions` is `0`.
+///
+/// # Panics
+///
+/// Panics on overflow, see [`checked_repeat`].
+pub fn repeat<T: Integer>(number: T, repetitions: u32) -> Option<T> {
+    (repetitions > 0).then(|| checked_repeat(number, repetitions).expect("attempt to repeat with overflow"))
+}
+
+/// Evaluates a polynomial with Horner's method, returns `None` on overflow.
+pub fn checked_horner<T: Integer>(polynomial: &[T], value: T) -> Option<T> {
+    polynomial.iter().try_fold(T::ZERO, |acc, &coefficient| value.checked_mul(acc)?.checked_add(coefficient))
 }
 
 /// Evaluates a polynomial with Horner's method, can also be used to convert a big-endian digit sequence into an integer
-pub fn horner(polynomial: &[u64], value: u64) -> u64 {
+///
+/// # Panics
+///
+/// Panics on overflow, see [`checked_horner`].
+pub fn horner<T: Integer>(polynomial: &[T], value: T) -> T {
+    checked_horner(polynomial, value).expect("attempt to evaluate a polynomial with overflow")
+}
+
+/// Returns the repunit made of `repetitions` ones spaced `block_len` digits apart, e.g. `repunit(2, 3) == 10101`.
+/// Multiplying a `block_len`-digit block by it repeats the block `repetitions` times. Returns `None` on overflow.
+pub fn repunit(block_len: u32, repetitions: u32) -> Option<u128> {
+    let shift = 10u128.checked_pow(block_len)?;
+    let mut ret = 0u128;
+
+    for _ in 0 .. repetitions {
+        ret = ret.checked_mul(shift)?.checked_add(1)?;
+    }
+
+    Some(ret)
+}
+
+/// Sums the integers in `from ..= to`.
+fn arithmetic_series(from: u128, to: u128) -> u128 {
+    let (count, sum) = (to - from + 1, from + to);
+
+    // One of them is even, divide it first to avoid overflowing
+    if count.is_multiple_of(2) { count / 2 * sum } else { sum / 2 * count }
+}
+
+/// Sums the numbers in `from ..= to` made of a block (without leading zeros) repeated exactly `repetitions` times,
+/// e.g. `123123` for 2 repetitions. A number like `1111` counts both as `11` repeated twice and `1` repeated 4 times.
+/// Runs in constant time: for each block length, the numbers are the blocks of an interval times a repunit.
+pub fn sum_repeated(from: u64, to: u64, repetitions: u32) -> u128 {
+    let (from, to) = (from as u128, to as u128);
     let mut ret = 0;
 
-    for coefficient in polynomial {
-        ret = value * ret + coefficient;
+    if repetitions == 0 || from > to {
+        return 0;
+    }
+
+    // A u64 has at most 20 digits
+    for block_len in 1 ..= 20 / repetitions {
+        let multiplier = repunit(block_len, repetitions).unwrap();
+
+        // Blocks of block_len digits whose repetition is in the range
+        let first_block = 10u128.pow(block_len - 1).max(from.div_ceil(multiplier));
+        let last_block = (10u128.pow(block_len) - 1).min(to / multiplier);
+
+        if first_block <= last_block {
+            ret += multiplier * arithmetic_series(first_block, last_block);
+        }
     }
 
     ret
 }
 
-/// 3D squared euclidian distance
-pub fn squared_eucl_3d((x1, y1, z1): (u64, u64, u64), (x2, y2, z2): (u64, u64, u64)) -> u64 {
+/// Sums the numbers in `from ..= to` made of a block repeated at least twice, each number being counted once.
+///
+/// A number made of a block repeated `k` times is also a block repeated `d` times for every divisor `d` of `k`,
+/// so by inclusion-exclusion over the repetition counts the sum is `-μ(k) * sum_repeated(from, to, k)` over `k >= 2`.
+pub fn sum_repeated_at_least_twice(from: u64, to: u64) -> u128 {
+    // μ(k) for k in 0 ..= 20, a u64 has at most 20 digits
+    const MOBIUS: [i128; 21] = [0, 1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0, -1, 1, 1, 0, -1, 0, -1, 0];
+    let mut ret = 0i128;
+
+    for repetitions in 2 ..= 20 {
+        ret -= MOBIUS[repetitions as usize] * sum_repeated(from, to, repetitions) as i128;
+    }
+
+    ret as u128
+}
+
+/// 3D squared euclidian distance, returns `None` on overflow.
+pub fn checked_squared_eucl_3d<T: Integer>((x1, y1, z1): (T, T, T), (x2, y2, z2): (T, T, T)) -> Option<T::Unsigned> {
     let x_diff = x1.abs_diff(x2);
     let y_diff = y1.abs_diff(y2);
     let z_diff = z1.abs_diff(z2);
 
-    x_diff.pow(2) + y_diff.pow(2) + z_diff.pow(2)
+    x_diff.checked_pow(2)?.checked_add(y_diff.checked_pow(2)?)?.checked_add(z_diff.checked_pow(2)?)
+}
+
+/// 3D squared euclidian distance
+///
+/// # Panics
+///
+/// Panics on overflow, see [`checked_squared_eucl_3d`].
+pub fn squared_eucl_3d<T: Integer>(a: (T, T, T), b: (T, T, T)) -> T::Unsigned {
+    checked_squared_eucl_3d(a, b).expect("attempt to compute a distance with overflow")
 }
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
+
     use super::*;
     #[test]
     fn test_num_digits() {
-        assert_eq!(num_digits(0), 1);
-        assert_eq!(num_digits(1), 1);
-        assert_eq!(num_digits(10), 2);
-        assert_eq!(num_digits(11), 2);
-        assert_eq!(num_digits(123), 3);
-        assert_eq!(num_digits(999), 3);
-        assert_eq!(num_digits(1011), 4);
-        assert_eq!(num_digits(1000000000), 10);
+        assert_eq!(num_digits::<u64>(0), 1);
+        assert_eq!(num_digits::<u64>(1), 1);
+        assert_eq!(num_digits::<u64>(10), 2);
+        assert_eq!(num_digits::<u64>(11), 2);
+        assert_eq!(num_digits::<u64>(123), 3);
+        assert_eq!(num_digits::<u64>(999), 3);
+        assert_eq!(num_digits::<u64>(1011), 4);
+        assert_eq!(num_digits::<u64>(1000000000), 10);
     }
 
     #[test]
     fn test_split_in_half() {
-        assert_eq!(split_in_half(11), Some((1, 1)));
-        assert_eq!(split_in_half(1011), Some((10, 11)));
-        assert_eq!(split_in_half(123), None);
+        assert_eq!(split_in_half::<u64>(11), Some((1, 1)));
+        assert_eq!(split_in_half::<u64>(1011), Some((10, 11)));
+        assert_eq!(split_in_half::<u64>(123), None);
     }
 
     #[test]
     fn test_split_in_parts() {
-        assert_eq!(split_in_parts(11, 2), Some(vec![1, 1]));
-        assert_eq!(split_in_parts(1011, 2), Some(vec![11, 10]));
-        assert_eq!(split_in_parts(123, 2), None);
-        assert_eq!(split_in_parts(123, 3), Some(vec![3, 2, 1]));
-        assert_eq!(split_in_parts(12345, 3), None);
-        assert_eq!(split_in_parts(123456, 3), Some(vec![56, 34, 12]));
-        assert_eq!(split_in_parts(123456, 2), Some(vec![456, 123]));
+        assert_eq!(split_in_parts::<u64>(11, 2), Some(vec![1, 1]));
+        assert_eq!(split_in_parts::<u64>(1011, 2), Some(vec![11, 10]));
+        assert_eq!(split_in_parts::<u64>(123, 2), None);
+        assert_eq!(split_in_parts::<u64>(123, 3), Some(vec![3, 2, 1]));
+        assert_eq!(split_in_parts::<u64>(12345, 3), None);
+        assert_eq!(split_in_parts::<u64>(123456, 3), Some(vec![56, 34, 12]));
+        assert_eq!(split_in_parts::<u64>(123456, 2), Some(vec![456, 123]));
     }
 
     #[test]
     fn test_concat() {
-        assert_eq!(concat(12, 3), 123);
-        assert_eq!(concat(1, 34), 134);
-        assert_eq!(concat(123, 456), 123456);
-        assert_eq!(concat(1200, 34), 120034);
+        assert_eq!(concat::<u64>(12, 3), 123);
+        assert_eq!(concat::<u64>(1, 34), 134);
+        assert_eq!(concat::<u64>(123, 456), 123456);
+        assert_eq!(concat::<u64>(1200, 34), 120034);
     }
 
     #[test]
     fn test_concat_list() {
-        assert_eq!(concat_list(&[3, 12]), Some(123));
-        assert_eq!(concat_list(&[34, 1]), Some(134));
-        assert_eq!(concat_list(&[456, 123]), Some(123456));
-        assert_eq!(concat_list(&[34, 1200]), Some(120034));
-        assert_eq!(concat_list(&[]), None);
-        assert_eq!(concat_list(&[3, 2, 1]), Some(123));
-        assert_eq!(concat_list(&[56, 34, 12]), Some(123456));
-        assert_eq!(concat_list(&[456, 123]), Some(123456));
+        assert_eq!(concat_list::<u64>(&[3, 12]), Some(123));
+        assert_eq!(concat_list::<u64>(&[34, 1]), Some(134));
+        assert_eq!(concat_list::<u64>(&[456, 123]), Some(123456));
+        assert_eq!(concat_list::<u64>(&[34, 1200]), Some(120034));
+        assert_eq!(concat_list::<u64>(&[]), None);
+        assert_eq!(concat_list::<u64>(&[3, 2, 1]), Some(123));
+        assert_eq!(concat_list::<u64>(&[56, 34, 12]), Some(123456));
+        assert_eq!(concat_list::<u64>(&[456, 123]), Some(123456));
     }
 
     #[test]
     fn test_repeat() {
-        assert_eq!(repeat(1, 3), Some(111));
-        assert_eq!(repeat(28, 5), Some(2828282828));
-        assert_eq!(repeat(0, 12), Some(0));
-        assert_eq!(repeat(42, 0), None);
+        assert_eq!(repeat::<u64>(1, 3), Some(111));
+        assert_eq!(repeat::<u64>(28, 5), Some(2828282828));
+        assert_eq!(repeat::<u64>(0, 12), Some(0));
+        assert_eq!(repeat::<u64>(42, 0), None);
     }
 
     #[test]
     fn test_horner() {
-        assert_eq!(horner(&[], 42), 0);
-        assert_eq!(horner(&[1, 2, 3], 10), 123);
-        assert_eq!(horner(&[10, 2, 3, 12], 2), 106);
+        assert_eq!(horner::<u64>(&[], 42), 0);
+        assert_eq!(horner::<u64>(&[1, 2, 3], 10), 123);
+        assert_eq!(horner::<u64>(&[10, 2, 3, 12], 2), 106);
+    }
+
+    #[test]
+    fn test_other_widths() {
+        assert_eq!(num_digits(u128::MAX), 39);
+        assert_eq!(num_digits(-1234i64), 4);
+        assert_eq!(num_digits(i64::MIN), 19);
+        assert_eq!(num_digits(255u8), 3);
+        assert_eq!(split_in_half(-1011i32), Some((-10, -11)));
+        assert_eq!(split_in_parts(u64::MAX, 1), Some(vec![u64::MAX]));
+        assert_eq!(concat_list(&[-456i64, -123]), Some(-123456));
+        assert_eq!(repeat(-12i16, 2), Some(-1212));
+        assert_eq!(repeat(123456789u128, 4), Some(123456789123456789123456789123456789));
+        assert_eq!(horner(&[1i64, -2, 3], -10), 123);
+        assert_eq!(squared_eucl_3d((-1i64, 2, -3), (2, -2, 9)), 169u64);
+    }
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(checked_concat(12u8, 3), Some(123));
+        assert_eq!(checked_concat(12u8, 34), None);
+        assert_eq!(checked_concat(u64::MAX / 10, 5), Some(u64::MAX));
+        assert_eq!(checked_concat(u64::MAX / 10, 6), None);
+        assert_eq!(checked_concat_list(&[5u64, 9, 1]), Some(195));
+        assert_eq!(checked_concat_list::<u64>(&[]), None);
+        assert_eq!(checked_concat_list(&[48i32, 21474836]), None);
+        assert_eq!(checked_concat_list(&[47i32, 21474836]), Some(i32::MAX));
+        assert_eq!(checked_repeat(28u64, 5), Some(2828282828));
+        assert_eq!(checked_repeat(28u32, 5), Some(2828282828));
+        assert_eq!(checked_repeat(28u32, 6), None);
+        assert_eq!(checked_repeat(28u32, 0), None);
+        assert_eq!(checked_horner(&[1u8, 2, 3], 10), Some(123));
+        assert_eq!(checked_horner(&[2u8, 5, 6], 10), None);
+        assert_eq!(checked_squared_eucl_3d((0u32, 0, 0), (65535, 0, 0)), Some(4294836225));
+        assert_eq!(checked_squared_eucl_3d((0u32, 0, 0), (65536, 0, 0)), None);
+        assert_eq!(checked_squared_eucl_3d((0i8, 0, 0), (-128, 0, 0)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn test_concat_overflow() {
+        concat(u64::MAX, 1);
+    }
+
+    #[test]
+    fn test_repunit() {
+        assert_eq!(repunit(1, 3), Some(111));
+        assert_eq!(repunit(2, 3), Some(10101));
+        assert_eq!(repunit(3, 0), Some(0));
+        assert_eq!(repunit(20, 2), Some(10u128.pow(20) + 1));
+        assert_eq!(repunit(20, 3), None);
+    }
+
+    fn is_repeated(n: u64, repetitions: u32) -> bool {
+        let len = num_digits(n);
+        len.is_multiple_of(repetitions) && split_in_parts(n, repetitions).unwrap().iter().all_equal()
+    }
+
+    #[test]
+    fn test_sum_repeated() {
+        for (from, to) in [(11, 22), (95, 115), (998, 1012), (1, 123456), (222220, 222224)] {
+            for repetitions in 1 ..= 6 {
+                let expected: u128 = (from ..= to).filter(|&n| is_repeated(n, repetitions)).map(u128::from).sum();
+                assert_eq!(sum_repeated(from, to, repetitions), expected);
+            }
+
+            let expected: u128 = (from ..= to).filter(|&n| (2 ..= 6).any(|k| is_repeated(n, k))).map(u128::from).sum();
+            assert_eq!(sum_repeated_at_least_twice(from, to), expected);
+        }
+
+        assert_eq!(sum_repeated(5, 4, 2), 0);
+        assert_eq!(sum_repeated(0, u64::MAX, 1), u64::MAX as u128 * (u64::MAX as u128 + 1) / 2);
+        assert_eq!(sum_repeated_at_least_twice(u64::MAX - 1, u64::MAX), 0);
+
+        // Every repetition count of a u64 is covered by the Möbius table
+        assert_eq!(sum_repeated_at_least_twice(10u64.pow(19), u64::MAX), 12014130244457775013632566892);
+        assert_eq!(sum_repeated_at_least_twice(100000, 999999), 539589960);
+    }
+
+    #[test]
+    fn test_checked_accumulate() {
+        assert_eq!([1u8, 2, 3].into_iter().checked_sum(), Some(6));
+        assert_eq!([100u8, 100, 100].into_iter().checked_sum(), None);
+        assert_eq!([-5i64, 3].into_iter().checked_product(), Some(-15));
+        assert_eq!([1u64 << 32, 1 << 32].into_iter().checked_product(), None);
+        assert_eq!(std::iter::empty::<u32>().checked_sum(), Some(0));
+        assert_eq!(std::iter::empty::<u32>().checked_product(), Some(1));
     }
 }
@@ -0,0 +1,295 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// A set of `u64` stored as sorted, disjoint and non-adjacent inclusive ranges.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    ranges: Vec<(u64, u64)>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sorts and merges `ranges`, which may overlap. Empty ranges are skipped.
+    fn normalize(mut ranges: Vec<(u64, u64)>) -> Self {
+        ranges.retain(|&(start, end)| start <= end);
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+
+    /// Adds every value of `range` to the set, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: RangeInclusive<u64>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        // Ranges in `first..last` overlap or touch the new one
+        let first = self
+            .ranges
+            .partition_point(|&(_, e)| e.saturating_add(1) < start);
+        let last = self
+            .ranges
+            .partition_point(|&(s, _)| s <= end.saturating_add(1));
+
+        if first < last {
+            start = start.min(self.ranges[first].0);
+            end = end.max(self.ranges[last - 1].1);
+        }
+        self.ranges.splice(first..last, [(start, end)]);
+    }
+
+    /// Returns `true` if `value` is in one of the ranges, in `O(log n)`.
+    pub fn contains(&self, value: u64) -> bool {
+        let index = self.ranges.partition_point(|&(_, end)| end < value);
+        self.ranges
+            .get(index)
+            .is_some_and(|&(start, _)| start <= value)
+    }
+
+    /// Returns `true` if the set has no values.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of disjoint ranges.
+    pub fn num_ranges(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Number of values in the set. It is a `u128` because the full `0..=u64::MAX` range has `2^64` values.
+    pub fn covered_len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| (end - start) as u128 + 1)
+            .sum()
+    }
+
+    /// Iterates over the ranges in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<u64>> {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    /// Iterates over the ranges of missing values between the smallest and the largest values of the set.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<u64>> {
+        self.ranges
+            .windows(2)
+            .map(|pair| pair[0].1 + 1..=pair[1].0 - 1)
+    }
+
+    /// Returns the values that are in `self` or `other`.
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        Self::normalize([self.ranges.as_slice(), &other.ranges].concat())
+    }
+
+    /// Returns the values that are in both `self` and `other`.
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(&(a_start, a_end)), Some(&(b_start, b_end))) =
+            (self.ranges.get(i), other.ranges.get(j))
+        {
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            // Drop the range that ends first, it can't overlap anything else
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Returns the values that are in `self` but not in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = vec![];
+        let mut j = 0;
+
+        for &(start, end) in &self.ranges {
+            // Skip the ranges of `other` that end before this one
+            while other.ranges.get(j).is_some_and(|&(_, e)| e < start) {
+                j += 1;
+            }
+
+            // Start of the part of the range that hasn't been removed yet, `None` once all of it has
+            let mut remaining = Some(start);
+            let mut k = j;
+            while let Some(from) = remaining
+                && let Some(&(removed_start, removed_end)) = other.ranges.get(k)
+                && removed_start <= end
+            {
+                if removed_start > from {
+                    ranges.push((from, removed_start - 1));
+                }
+                if removed_end >= end {
+                    // This range of `other` may also overlap the next range of `self`
+                    remaining = None;
+                } else {
+                    remaining = Some(removed_end + 1);
+                    k += 1;
+                }
+            }
+
+            if let Some(from) = remaining {
+                ranges.push((from, end));
+            }
+            j = k;
+        }
+
+        Self { ranges }
+    }
+}
+
+impl FromIterator<RangeInclusive<u64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<u64>>>(iter: I) -> Self {
+        Self::normalize(iter.into_iter().map(RangeInclusive::into_inner).collect())
+    }
+}
+
+impl Extend<RangeInclusive<u64>> for IntervalSet {
+    fn extend<I: IntoIterator<Item = RangeInclusive<u64>>>(&mut self, iter: I) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.extend(iter.into_iter().map(RangeInclusive::into_inner));
+        *self = Self::normalize(ranges);
+    }
+}
+
+/// Parses one range per line, written `a-b`. Blank lines are skipped.
+impl FromStr for IntervalSet {
+    type Err = IntervalSetFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                parse_range(line).ok_or_else(|| IntervalSetFromStrError {
+                    line: i + 1,
+                    content: line.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parses an inclusive range written `a-b`, returns `None` if it is malformed or if `a > b`.
+pub fn parse_range(s: &str) -> Option<RangeInclusive<u64>> {
+    let (start, end) = s.trim().split_once('-')?;
+    let (start, end) = (start.parse().ok()?, end.parse().ok()?);
+    (start <= end).then_some(start..=end)
+}
+
+/// An error which can be returned when parsing an [`IntervalSet`].
+#[derive(Debug)]
+pub struct IntervalSetFromStrError {
+    /// 1-based line number
+    pub line: usize,
+    pub content: String,
+}
+
+impl Error for IntervalSetFromStrError {}
+
+impl Display for IntervalSetFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: expecting a range `a-b` with a <= b, got \"{}\"",
+            self.line, self.content
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(u64, u64)]) -> IntervalSet {
+        ranges.iter().map(|&(start, end)| start..=end).collect()
+    }
+
+    fn ranges(set: &IntervalSet) -> Vec<(u64, u64)> {
+        set.iter().map(RangeInclusive::into_inner).collect()
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut intervals = IntervalSet::new();
+        intervals.insert(10..=14);
+        intervals.insert(3..=5);
+        intervals.insert(16..=20);
+        intervals.insert(12..=18);
+        assert_eq!(ranges(&intervals), vec![(3, 5), (10, 20)]);
+
+        intervals.insert(6..=9);
+        assert_eq!(ranges(&intervals), vec![(3, 20)]);
+
+        intervals.insert(u64::MAX - 1..=u64::MAX);
+        intervals.insert(0..=0);
+        assert_eq!(intervals.num_ranges(), 3);
+        assert_eq!(intervals.covered_len(), 21);
+
+        intervals.insert(1..=u64::MAX - 2);
+        assert_eq!(intervals.covered_len(), 1 << 64);
+    }
+
+    #[test]
+    fn test_contains() {
+        let intervals = set(&[(3, 5), (10, 14), (16, 20), (12, 18)]);
+        assert_eq!(ranges(&intervals), vec![(3, 5), (10, 20)]);
+        assert!(!intervals.contains(1));
+        assert!(intervals.contains(5));
+        assert!(!intervals.contains(8));
+        assert!(intervals.contains(17));
+        assert!(!intervals.contains(32));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(1, 10), (20, 30)]);
+        let b = set(&[(5, 8), (10, 22), (40, 50)]);
+
+        assert_eq!(ranges(&a.union(&b)), vec![(1, 30), (40, 50)]);
+        assert_eq!(
+            ranges(&a.intersection(&b)),
+            vec![(5, 8), (10, 10), (20, 22)]
+        );
+        assert_eq!(ranges(&a.difference(&b)), vec![(1, 4), (9, 9), (23, 30)]);
+        assert_eq!(ranges(&b.difference(&a)), vec![(11, 19), (40, 50)]);
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn test_gaps() {
+        let intervals = set(&[(1, 3), (5, 5), (10, 12)]);
+        assert_eq!(intervals.gaps().collect::<Vec<_>>(), vec![4..=4, 6..=9]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let intervals: IntervalSet = "3-5\n10-14\n\n16-20\n12-18\n".parse().unwrap();
+        assert_eq!(ranges(&intervals), vec![(3, 5), (10, 20)]);
+
+        let error = "3-5\n10-\n".parse::<IntervalSet>().unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!("5-3".parse::<IntervalSet>().is_err());
+    }
+}
@@ -4,3 +4,177 @@ pub fn neighboring_indices((i, j): (usize, usize), num_lines: usize, num_columns
     iproduct!(i.saturating_sub(1) .. (i + 2).min(num_lines), j.saturating_sub(1) .. (j + 2).min(num_columns))
         .filter(move |&(k, l)| k != i || l != j)
 }
+
+/// Which cells around an index are considered its neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+    /// Cells sharing a side, 4 in 2D (von Neumann neighborhood).
+    Orthogonal,
+    /// Cells sharing only a corner, 4 in 2D.
+    Diagonal,
+    /// Cells sharing a side or a corner, 8 in 2D (Moore neighborhood).
+    All,
+    /// Cells at Chebyshev distance at most `radius`, i.e. the `(2 * radius + 1)^N` cube minus its center.
+    Chebyshev(usize),
+    /// Cells at Manhattan distance at most `radius`.
+    Manhattan(usize),
+}
+
+impl Neighborhood {
+    fn radius(self) -> usize {
+        match self {
+            Neighborhood::Orthogonal | Neighborhood::Diagonal | Neighborhood::All => 1,
+            Neighborhood::Chebyshev(radius) | Neighborhood::Manhattan(radius) => radius,
+        }
+    }
+
+    fn contains(self, direction: &[isize]) -> bool {
+        if direction.iter().all(|&d| d == 0) {
+            return false;
+        }
+
+        match self {
+            Neighborhood::Orthogonal => direction.iter().map(|d| d.unsigned_abs()).sum::<usize>() == 1,
+            Neighborhood::Diagonal => direction.iter().all(|&d| d != 0),
+            Neighborhood::All | Neighborhood::Chebyshev(_) => true,
+            Neighborhood::Manhattan(radius) => direction.iter().map(|d| d.unsigned_abs()).sum::<usize>() <= radius,
+        }
+    }
+
+    /// Returns the offsets of the neighbors in `N` dimensions, in lexicographic order.
+    pub fn directions<const N: usize>(self) -> impl Iterator<Item = [isize; N]> {
+        let radius = self.radius() as isize;
+        let side = 2 * radius + 1;
+
+        (0 .. side.pow(N as u32))
+            .map(move |mut n| {
+                let mut direction = [0; N];
+                for d in direction.iter_mut().rev() {
+                    *d = n % side - radius;
+                    n /= side;
+                }
+                direction
+            })
+            .filter(move |direction| self.contains(direction))
+    }
+
+    /// Returns the offsets of the neighbors in 2D as `(delta_line, delta_column)`, in lexicographic order.
+    pub fn directions_2d(self) -> impl Iterator<Item = (isize, isize)> {
+        self.directions().map(|[di, dj]| (di, dj))
+    }
+}
+
+/// N-dimensional [`neighbors_with_direction`].
+pub fn neighbors_nd_with_direction<const N: usize>(index: [usize; N], shape: [usize; N], neighborhood: Neighborhood) -> impl Iterator<Item = ([usize; N], [isize; N])> {
+    neighborhood.directions().filter_map(move |direction| {
+        let mut neighbor = [0; N];
+        for k in 0 .. N {
+            neighbor[k] = index[k].checked_add_signed(direction[k]).filter(|&x| x < shape[k])?;
+        }
+        Some((neighbor, direction))
+    })
+}
+
+/// N-dimensional [`neighbors`].
+pub fn neighbors_nd<const N: usize>(index: [usize; N], shape: [usize; N], neighborhood: Neighborhood) -> impl Iterator<Item = [usize; N]> {
+    neighbors_nd_with_direction(index, shape, neighborhood).map(|(neighbor, _)| neighbor)
+}
+
+/// N-dimensional [`wrapping_neighbors_with_direction`].
+pub fn wrapping_neighbors_nd_with_direction<const N: usize>(index: [usize; N], shape: [usize; N], neighborhood: Neighborhood) -> impl Iterator<Item = ([usize; N], [isize; N])> {
+    neighborhood.directions().map(move |direction| {
+        let mut neighbor = [0; N];
+        for k in 0 .. N {
+            neighbor[k] = (index[k] as isize + direction[k]).rem_euclid(shape[k] as isize) as usize;
+        }
+        (neighbor, direction)
+    })
+}
+
+/// N-dimensional [`wrapping_neighbors`].
+pub fn wrapping_neighbors_nd<const N: usize>(index: [usize; N], shape: [usize; N], neighborhood: Neighborhood) -> impl Iterator<Item = [usize; N]> {
+    wrapping_neighbors_nd_with_direction(index, shape, neighborhood).map(|(neighbor, _)| neighbor)
+}
+
+/// Returns the neighbors of `(i, j)` along with their direction `(delta_line, delta_column)`, neighbors outside the grid are skipped.
+pub fn neighbors_with_direction((i, j): (usize, usize), num_lines: usize, num_columns: usize, neighborhood: Neighborhood) -> impl Iterator<Item = ((usize, usize), (isize, isize))> {
+    neighbors_nd_with_direction([i, j], [num_lines, num_columns], neighborhood)
+        .map(|([k, l], [di, dj])| ((k, l), (di, dj)))
+}
+
+/// Returns the neighbors of `(i, j)`, neighbors outside the grid are skipped.
+pub fn neighbors((i, j): (usize, usize), num_lines: usize, num_columns: usize, neighborhood: Neighborhood) -> impl Iterator<Item = (usize, usize)> {
+    neighbors_with_direction((i, j), num_lines, num_columns, neighborhood).map(|(neighbor, _)| neighbor)
+}
+
+/// Returns the neighbors of `(i, j)` along with their direction `(delta_line, delta_column)` on a torus: neighbors outside the grid wrap around to the other side.
+/// A radius at least as large as the grid yields some cells several times.
+pub fn wrapping_neighbors_with_direction((i, j): (usize, usize), num_lines: usize, num_columns: usize, neighborhood: Neighborhood) -> impl Iterator<Item = ((usize, usize), (isize, isize))> {
+    wrapping_neighbors_nd_with_direction([i, j], [num_lines, num_columns], neighborhood)
+        .map(|([k, l], [di, dj])| ((k, l), (di, dj)))
+}
+
+/// Returns the neighbors of `(i, j)` on a torus: neighbors outside the grid wrap around to the other side.
+pub fn wrapping_neighbors((i, j): (usize, usize), num_lines: usize, num_columns: usize, neighborhood: Neighborhood) -> impl Iterator<Item = (usize, usize)> {
+    wrapping_neighbors_with_direction((i, j), num_lines, num_columns, neighborhood).map(|(neighbor, _)| neighbor)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn test_neighboring_indices() {
+        assert_eq!(neighboring_indices((0, 0), 3, 3).collect_vec(), vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(neighboring_indices((1, 1), 3, 3).count(), 8);
+        assert_eq!(neighboring_indices((1, 1), 3, 3).collect_vec(), neighbors((1, 1), 3, 3, Neighborhood::All).collect_vec());
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Neighborhood::Orthogonal.directions_2d().collect_vec(), vec![(-1, 0), (0, -1), (0, 1), (1, 0)]);
+        assert_eq!(Neighborhood::Diagonal.directions_2d().collect_vec(), vec![(-1, -1), (-1, 1), (1, -1), (1, 1)]);
+        assert_eq!(Neighborhood::All.directions_2d().count(), 8);
+        assert_eq!(Neighborhood::Chebyshev(2).directions_2d().count(), 24);
+        assert_eq!(Neighborhood::Manhattan(2).directions_2d().count(), 12);
+        assert_eq!(Neighborhood::Chebyshev(0).directions_2d().count(), 0);
+        assert_eq!(Neighborhood::All.directions::<3>().count(), 26);
+        assert_eq!(Neighborhood::Orthogonal.directions::<4>().count(), 8);
+        assert_eq!(Neighborhood::Diagonal.directions::<3>().count(), 8);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        assert_eq!(neighbors((0, 0), 3, 3, Neighborhood::Orthogonal).collect_vec(), vec![(0, 1), (1, 0)]);
+        assert_eq!(neighbors((0, 2), 3, 3, Neighborhood::Diagonal).collect_vec(), vec![(1, 1)]);
+        assert_eq!(neighbors((0, 0), 5, 5, Neighborhood::Manhattan(2)).count(), 5);
+        assert_eq!(
+            neighbors_with_direction((1, 0), 3, 3, Neighborhood::Orthogonal).collect_vec(),
+            vec![((0, 0), (-1, 0)), ((1, 1), (0, 1)), ((2, 0), (1, 0))]
+        );
+    }
+
+    #[test]
+    fn test_wrapping_neighbors() {
+        assert_eq!(wrapping_neighbors((0, 0), 3, 4, Neighborhood::Orthogonal).collect_vec(), vec![(2, 0), (0, 3), (0, 1), (1, 0)]);
+        assert_eq!(wrapping_neighbors((0, 0), 3, 4, Neighborhood::All).count(), 8);
+        assert_eq!(
+            wrapping_neighbors_with_direction((2, 3), 3, 4, Neighborhood::Diagonal).last(),
+            Some(((0, 0), (1, 1)))
+        );
+    }
+
+    #[test]
+    fn test_neighbors_nd() {
+        assert_eq!(neighbors_nd([0, 0, 0], [3, 3, 3], Neighborhood::All).count(), 7);
+        assert_eq!(neighbors_nd([1, 1, 1, 1], [3, 3, 3, 3], Neighborhood::All).count(), 80);
+        assert_eq!(
+            neighbors_nd_with_direction([0, 0, 0], [2, 2, 2], Neighborhood::Orthogonal).collect_vec(),
+            vec![([0, 0, 1], [0, 0, 1]), ([0, 1, 0], [0, 1, 0]), ([1, 0, 0], [1, 0, 0])]
+        );
+        assert_eq!(wrapping_neighbors_nd([0, 0, 0], [5, 5, 5], Neighborhood::Orthogonal).collect_vec()[0], [4, 0, 0]);
+        assert_eq!(wrapping_neighbors_nd_with_direction([0, 0, 0], [5, 5, 5], Neighborhood::All).count(), 26);
+    }
+}
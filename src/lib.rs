@@ -1,5 +1,21 @@
 pub mod template;
 
 // Use this file to add helper functions and additional modules.
+pub mod automaton;
+pub mod big_uint;
+pub mod columns;
+pub mod cycle;
+pub mod geometry;
+pub mod grid;
+pub mod image;
+pub mod input;
 pub mod int_utils;
+pub mod interval_set;
 pub mod iter_utils;
+pub mod memo;
+pub mod number_theory;
+pub mod search;
+pub mod sieve;
+pub mod spatial;
+pub mod union_find;
+pub mod visualize;
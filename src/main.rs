@@ -1,4 +1,5 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{all, completions, download, read, scaffold, solve, time};
+use advent_of_code::template::usage;
 use args::{AppArguments, parse};
 
 #[cfg(feature = "today")]
@@ -7,8 +8,18 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::{
+        Day,
+        commands::{
+            completions::Shell,
+            scaffold::{Artifact, parse_artifacts},
+        },
+        usage::{self, CommandUsage},
+    };
+    use std::ffi::OsString;
+    use std::fmt::Display;
     use std::process;
+    use std::str::FromStr;
 
     pub enum AppArguments {
         Download {
@@ -20,13 +31,19 @@ mod args {
         Scaffold {
             day: Day,
             download: bool,
-            overwrite: bool,
+            overwrite: Vec<Artifact>,
+            dry_run: bool,
+            template: Option<String>,
+            example_answers: [Option<String>; 2],
         },
         Solve {
             day: Day,
             release: bool,
             dhat: bool,
+            visualize: bool,
             submit: Option<u8>,
+            watch: bool,
+            test: bool,
         },
         All {
             release: bool,
@@ -36,60 +53,241 @@ mod args {
             day: Option<Day>,
             store: bool,
         },
+        Completions {
+            shell: Shell,
+        },
+        Help {
+            command: Option<&'static CommandUsage>,
+        },
         #[cfg(feature = "today")]
         Today,
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
+        let help = args.contains(["-h", "--help"]);
+
+        let Some(name) = args.subcommand()? else {
+            if help {
+                return Ok(AppArguments::Help { command: None });
+            }
+            eprintln!("No command specified.");
+            eprintln!();
+            eprint!("{}", usage::help());
+            process::exit(1);
+        };
+
+        let Some(command) = usage::find_command(&name) else {
+            eprintln!("Unknown command: {name}");
+            if let Some(suggestion) = usage::suggest_command(&name) {
+                eprintln!("Did you mean `{suggestion}`?");
+            }
+            eprintln!();
+            eprint!("{}", usage::help());
+            process::exit(1);
+        };
+
+        if help {
+            return Ok(AppArguments::Help {
+                command: Some(command),
+            });
+        }
+
+        parse_command(command, args).map_err(|err| {
+            let err = match err.downcast_ref::<pico_args::Error>() {
+                Some(pico_args::Error::MissingArgument) => {
+                    let missing = command
+                        .args
+                        .iter()
+                        .filter(|arg| arg.required)
+                        .map(|arg| format!("`{}`", arg.placeholder()))
+                        .collect::<Vec<_>>();
+                    format!("missing argument {}.", missing.join(" "))
+                }
+                _ => err.to_string(),
+            };
+
+            format!(
+                "{err}\n\nUsage: cargo {}\nRun `cargo {} --help` for more information.",
+                command.usage_line(),
+                command.name
+            )
+            .into()
+        })
+    }
+
+    /// Fails with a message listing `remaining`, the arguments that were not recognized, if there are any.
+    fn check_unknown(
+        command: &CommandUsage,
+        remaining: &[OsString],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let unknown: Vec<String> = remaining
+            .iter()
+            .map(|arg| {
+                let arg = arg.to_string_lossy();
+                match command.suggest_option(&arg) {
+                    Some(suggestion) if arg.starts_with('-') => {
+                        format!("unknown option `{arg}` (did you mean `{suggestion}`?)")
+                    }
+                    _ if arg.starts_with('-') => format!("unknown option `{arg}`"),
+                    _ => format!("unexpected argument `{arg}`"),
+                }
+            })
+            .collect();
+
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("{}.", unknown.join(", ")).into())
+        }
+    }
+
+    /// Fails on the options left once the known ones are parsed, which would otherwise be taken for a free-standing
+    /// argument, e.g. the day in `cargo solve --relase 5`.
+    fn check_unknown_options(
+        command: &CommandUsage,
+        args: &pico_args::Arguments,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let options: Vec<OsString> = args
+            .clone()
+            .finish()
+            .into_iter()
+            .filter(|arg| arg.to_string_lossy().starts_with('-'))
+            .collect();
+        check_unknown(command, &options)
+    }
+
+    /// Parses the next free-standing argument, see [`check_unknown_options`].
+    fn free<T: FromStr>(
+        command: &CommandUsage,
+        args: &mut pico_args::Arguments,
+    ) -> Result<T, Box<dyn std::error::Error>>
+    where
+        T::Err: Display,
+    {
+        check_unknown_options(command, args)?;
+        Ok(args.free_from_str()?)
+    }
+
+    /// Parses the next optional free-standing argument, see [`check_unknown_options`].
+    fn opt_free<T: FromStr>(
+        command: &CommandUsage,
+        args: &mut pico_args::Arguments,
+    ) -> Result<Option<T>, Box<dyn std::error::Error>>
+    where
+        T::Err: Display,
+    {
+        check_unknown_options(command, args)?;
+        Ok(args.opt_free_from_str()?)
+    }
 
-        let app_args = match args.subcommand()?.as_deref() {
-            Some("all") => AppArguments::All {
+    // NOTE: flags and options are parsed before free-standing arguments, so they can be placed anywhere.
+    fn parse_command(
+        command: &CommandUsage,
+        mut args: pico_args::Arguments,
+    ) -> Result<AppArguments, Box<dyn std::error::Error>> {
+        let app_args = match command.name {
+            "all" => AppArguments::All {
                 release: args.contains("--release"),
             },
-            Some("time") => {
+            "time" => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
 
                 AppArguments::Time {
                     all,
-                    day: args.opt_free_from_str()?,
+                    day: opt_free(command, &mut args)?,
                     store,
                 }
             }
-            Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
-            },
-            Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
+            "download" => AppArguments::Download {
+                day: free(command, &mut args)?,
             },
-            Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
-                download: args.contains("--download"),
-                overwrite: args.contains("--overwrite"),
+            "read" => AppArguments::Read {
+                day: free(command, &mut args)?,
             },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
+            "scaffold" => {
+                // NOTE: a bare `--overwrite` only protects the module file, as it used to.
+                let mut overwrite = if args.contains("--overwrite") {
+                    vec![Artifact::Module]
+                } else {
+                    vec![]
+                };
+
+                if let Some(artifacts) = args.opt_value_from_fn("--overwrite", parse_artifacts)? {
+                    overwrite.extend(artifacts);
+                }
+
+                let download = args.contains("--download");
+                let dry_run = args.contains("--dry-run");
+                let template = args.opt_value_from_str("--template")?;
+                let example_answers = [
+                    args.opt_value_from_str("--example-1")?,
+                    args.opt_value_from_str("--example-2")?,
+                ];
+
+                AppArguments::Scaffold {
+                    day: free(command, &mut args)?,
+                    download,
+                    overwrite,
+                    dry_run,
+                    template,
+                    example_answers,
+                }
+            }
+            "solve" => {
+                let release = args.contains("--release");
+                let submit = args.opt_value_from_str("--submit")?;
+                let dhat = args.contains("--dhat");
+                let visualize = args.contains("--visualize");
+                let watch = args.contains("--watch");
+                let test = args.contains("--test");
+
+                if watch && submit.is_some() {
+                    return Err("`--submit` cannot be combined with `--watch`.".into());
+                }
+
+                if test && !watch {
+                    return Err("`--test` can only be used together with `--watch`.".into());
+                }
+
+                AppArguments::Solve {
+                    day: free(command, &mut args)?,
+                    release,
+                    dhat,
+                    visualize,
+                    submit,
+                    watch,
+                    test,
+                }
+            }
+            "completions" => AppArguments::Completions {
+                shell: free(command, &mut args)?,
             },
-            #[cfg(feature = "today")]
-            Some("today") => AppArguments::Today,
-            Some(x) => {
-                eprintln!("Unknown command: {x}");
-                process::exit(1);
+            "help" => {
+                let name: Option<String> = opt_free(command, &mut args)?;
+                let command = match name {
+                    Some(name) => Some(
+                        usage::find_command(&name)
+                            .ok_or_else(|| format!("unknown command `{name}`."))?,
+                    ),
+                    None => None,
+                };
+
+                AppArguments::Help { command }
             }
-            None => {
-                eprintln!("No command specified.");
-                process::exit(1);
+            #[cfg(feature = "today")]
+            "today" => AppArguments::Today,
+            #[cfg(not(feature = "today"))]
+            "today" => {
+                return Err(
+                    "the `today` command requires the `today` feature, use `cargo today`.".into(),
+                );
             }
+            _ => unreachable!("commands are validated against `usage::COMMANDS`"),
         };
 
-        let remaining = args.finish();
-        if !remaining.is_empty() {
-            eprintln!("Warning: unknown argument(s): {remaining:?}.");
-        }
+        check_unknown(command, &args.finish())?;
 
         Ok(app_args)
     }
@@ -104,30 +302,58 @@ fn main() {
         Ok(args) => match args {
             AppArguments::All { release } => all::handle(release),
             AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
+            AppArguments::Download { day } => download::handle(day, true),
             AppArguments::Read { day } => read::handle(day),
+            AppArguments::Completions { shell } => completions::handle(shell),
+            AppArguments::Help { command } => match command {
+                Some(command) => print!("{}", command.help()),
+                None => print!("{}", usage::help()),
+            },
             AppArguments::Scaffold {
                 day,
                 download,
                 overwrite,
+                dry_run,
+                template,
+                example_answers,
             } => {
-                scaffold::handle(day, overwrite);
+                // Download first, the template needs the puzzle title
                 if download {
-                    download::handle(day);
+                    if dry_run {
+                        println!("🎄 Dry run, skipped downloading the input.");
+                    } else {
+                        download::handle(day, overwrite.contains(&scaffold::Artifact::Input));
+                    }
                 }
+                scaffold::handle(
+                    day,
+                    &overwrite,
+                    dry_run,
+                    template.as_deref(),
+                    example_answers,
+                );
             }
             AppArguments::Solve {
                 day,
                 release,
                 dhat,
+                visualize,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                watch,
+                test,
+            } => {
+                if watch {
+                    solve::handle_watch(day, release, dhat, visualize, test);
+                } else {
+                    solve::handle(day, release, dhat, visualize, submit);
+                }
+            }
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
                     Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
+                        download::handle(day, false);
+                        scaffold::handle(day, &[], false, None, [None, None]);
                         read::handle(day)
                     }
                     None => {
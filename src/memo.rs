@@ -0,0 +1,400 @@
+/// Memoization of pure recursive functions, see [`memo!`](crate::memo!).
+use std::cell::{Cell, RefCell};
+#[cfg(debug_assertions)]
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::thread::LocalKey;
+
+thread_local! {
+    /// Incremented by [`clear_all`], caches filled during an older generation are stale
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+
+    #[cfg(debug_assertions)]
+    static STATS: RefCell<BTreeMap<&'static str, MemoStats>> = RefCell::default();
+}
+
+/// Number of calls of a memoized function that were answered from its cache, and that had to be computed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl MemoStats {
+    /// Proportion of the calls answered from the cache, `0` if there were no calls.
+    pub fn hit_rate(&self) -> f64 {
+        let calls = self.hits + self.misses;
+        if calls == 0 {
+            0.0
+        } else {
+            self.hits as f64 / calls as f64
+        }
+    }
+}
+
+/// Cache of the values of a function, keyed on its arguments. The [`memo!`](crate::memo!) macro gives every memoized
+/// function its own thread-local cache.
+#[derive(Debug, Clone)]
+pub struct MemoCache<K, V> {
+    /// Name of the function, used for the statistics
+    name: &'static str,
+    values: HashMap<K, V>,
+    generation: u64,
+    /// [`fingerprint`] of the arguments that are not part of the key, that the values were computed with
+    context: Option<u64>,
+    /// Number of calls of the function in progress, to only check the context on the outermost one
+    depth: usize,
+}
+
+impl<K: Hash + Eq, V: Clone> MemoCache<K, V> {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            values: HashMap::new(),
+            generation: GENERATION.get(),
+            context: None,
+            depth: 0,
+        }
+    }
+
+    /// Empties the cache if [`clear_all`] was called since it was last used.
+    fn check_generation(&mut self) {
+        let generation = GENERATION.get();
+        if self.generation != generation {
+            self.values.clear();
+            self.generation = generation;
+        }
+    }
+
+    /// Starts a call of the function. On the outermost call, the cache is emptied if the [`fingerprint`] of the
+    /// arguments that are not part of the key changed: the values were computed for another input. Recursive calls
+    /// are assumed to pass these arguments along, so they are not checked again.
+    pub fn enter(&mut self, context: impl FnOnce() -> u64) {
+        self.check_generation();
+
+        if self.depth == 0 {
+            let context = Some(context());
+            if self.context != context {
+                self.values.clear();
+                self.context = context;
+            }
+        }
+        self.depth += 1;
+    }
+
+    /// Returns the cached value for `key`, counting a hit or a miss in debug builds.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        self.check_generation();
+
+        let value = self.values.get(key).cloned();
+
+        #[cfg(debug_assertions)]
+        STATS.with_borrow_mut(|stats| {
+            let stats = stats.entry(self.name).or_default();
+            if value.is_some() {
+                stats.hits += 1;
+            } else {
+                stats.misses += 1;
+            }
+        });
+
+        value
+    }
+
+    /// Name of the memoized function.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.values.insert(key, value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+impl<K, V> MemoCache<K, V> {
+    /// Ends a call started with [`MemoCache::enter`].
+    fn exit(&mut self) {
+        self.depth -= 1;
+    }
+}
+
+/// Ends a call of a memoized function when dropped, even if it panics.
+#[doc(hidden)]
+pub struct CallGuard<K: 'static, V: 'static>(&'static LocalKey<RefCell<MemoCache<K, V>>>);
+
+impl<K, V> CallGuard<K, V> {
+    pub fn new(cache: &'static LocalKey<RefCell<MemoCache<K, V>>>) -> Self {
+        Self(cache)
+    }
+}
+
+impl<K, V> Drop for CallGuard<K, V> {
+    fn drop(&mut self) {
+        self.0.with_borrow_mut(MemoCache::exit);
+    }
+}
+
+/// Identifies the value behind a reference by its address and size, in constant time whatever its type. A value
+/// modified in place, or dropped and replaced by another one at the same address, has the same identity.
+#[doc(hidden)]
+pub fn identity<T: ?Sized>(value: &T) -> (usize, usize) {
+    (
+        std::ptr::from_ref(value).cast::<()>() as usize,
+        size_of_val(value),
+    )
+}
+
+/// Hash of the [`identity`] of the arguments of a memoized function that are not part of the key.
+#[doc(hidden)]
+pub fn fingerprint(identities: &[(usize, usize)]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    identities.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Empties the caches of all the memoized functions of the current thread and resets their statistics.
+///
+/// The runner calls it before every run of a solution part, and [`read_file`](crate::template::read_file) whenever an
+/// input is read. Tests run on their own thread, so they never share caches.
+pub fn clear_all() {
+    GENERATION.set(GENERATION.get() + 1);
+
+    #[cfg(debug_assertions)]
+    STATS.with_borrow_mut(BTreeMap::clear);
+}
+
+/// Hit and miss counts of the memoized functions called on the current thread since the last [`clear_all`], sorted by
+/// function name.
+#[cfg(debug_assertions)]
+pub fn stats() -> Vec<(&'static str, MemoStats)> {
+    STATS.with_borrow(|stats| stats.iter().map(|(&name, &stats)| (name, stats)).collect())
+}
+
+/// Statistics are only collected in debug builds.
+#[cfg(not(debug_assertions))]
+pub fn stats() -> Vec<(&'static str, MemoStats)> {
+    Vec::new()
+}
+
+/// Defines a function whose values are cached in a [`HashMap`] keyed on its arguments, for recursive dynamic
+/// programming. The arguments and the return type must implement `Clone`, the arguments `Hash` and `Eq` too, and they
+/// can't borrow anything.
+///
+/// Arguments written before a `;` are references that are not part of the key, e.g. the parsed puzzle input. They are
+/// identified by the address and size of what they point to: the cache is emptied when the outermost call gets other
+/// ones, so calling the function with another input doesn't return stale values. Recursive calls must pass them along
+/// unchanged. A value modified or rebuilt at the same place, e.g. a graph built on the stack in a loop, keeps its
+/// identity: call [`memo::clear_all`](crate::memo::clear_all) before using it again.
+///
+/// ```
+/// advent_of_code::memo! {
+///     fn count_paths(graph: &[Vec<usize>]; from: usize, to: usize) -> u64 {
+///         if from == to {
+///             return 1;
+///         }
+///         graph[from].iter().map(|&next| count_paths(graph, next, to)).sum()
+///     }
+/// }
+///
+/// let graph = vec![vec![1, 2], vec![2, 3], vec![3], vec![]];
+/// assert_eq!(count_paths(&graph, 0, 3), 3);
+/// ```
+#[macro_export]
+macro_rules! memo {
+    (
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident(
+            $($context:ident: &$context_ty:ty),* ; $($arg:ident: $arg_ty:ty),* $(,)?
+        ) -> $ret:ty $body:block
+    ) => {
+        $(#[$meta])*
+        $vis fn $name($($context: &$context_ty,)* $($arg: $arg_ty),*) -> $ret {
+            ::std::thread_local! {
+                static CACHE: ::std::cell::RefCell<$crate::memo::MemoCache<($($arg_ty,)*), $ret>> =
+                    ::std::cell::RefCell::new($crate::memo::MemoCache::new(::std::stringify!($name)));
+            }
+
+            fn compute($($context: &$context_ty,)* $($arg: $arg_ty),*) -> $ret $body
+
+            CACHE.with_borrow_mut(|cache| {
+                cache.enter(|| $crate::memo::fingerprint(&[$($crate::memo::identity($context)),*]))
+            });
+            let _guard = $crate::memo::CallGuard::new(&CACHE);
+
+            let key = ($(::std::clone::Clone::clone(&$arg),)*);
+            if let ::std::option::Option::Some(value) = CACHE.with_borrow_mut(|cache| cache.get(&key)) {
+                return value;
+            }
+
+            let value = compute($($context,)* $($arg),*);
+            CACHE.with_borrow_mut(|cache| cache.insert(key, ::std::clone::Clone::clone(&value)));
+            value
+        }
+    };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident($($arg:ident: $arg_ty:ty),* $(,)?) -> $ret:ty $body:block
+    ) => {
+        $crate::memo! {
+            $(#[$meta])*
+            $vis fn $name(; $($arg: $arg_ty),*) -> $ret $body
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::memo! {
+        /// Number of ways to climb `n` stairs by steps of 1, 2 or 3.
+        fn stairs(n: u64) -> u64 {
+            match n {
+                0 => 1,
+                1 => 1,
+                2 => 2,
+                _ => stairs(n - 1) + stairs(n - 2) + stairs(n - 3),
+            }
+        }
+    }
+
+    crate::memo! {
+        fn arrangements(pattern: &[u8]; start: usize, prefix: String) -> Vec<String> {
+            if start == pattern.len() {
+                return vec![prefix];
+            }
+            let mut ret = arrangements(pattern, start + 1, format!("{prefix}{}", pattern[start] as char));
+            if pattern[start] == b'?' {
+                ret.extend(arrangements(pattern, start + 1, format!("{prefix}#")));
+            }
+            ret
+        }
+    }
+
+    #[test]
+    fn test_memo() {
+        // Would take ages without the cache
+        assert_eq!(stairs(70), 2073693258389777176);
+        assert_eq!(stairs(4), 7);
+
+        let arrangements = arrangements(b"?.?", 0, String::new());
+        assert_eq!(arrangements, ["?.?", "?.#", "#.?", "#.#"]);
+    }
+
+    crate::memo! {
+        fn suffix_sum(values: &[u64]; start: usize) -> u64 {
+            if start == values.len() {
+                return 0;
+            }
+            values[start] + suffix_sum(values, start + 1)
+        }
+    }
+
+    #[test]
+    fn test_context() {
+        // Another input on the same thread doesn't reuse the values of the previous one
+        assert_eq!(suffix_sum(&[1, 2, 3], 0), 6);
+        assert_eq!(suffix_sum(&[10, 20, 30], 0), 60);
+        assert_eq!(suffix_sum(&[10, 20, 30], 1), 50);
+
+        // Modified in place, the values must be cleared
+        let mut values = vec![1, 2, 3];
+        assert_eq!(suffix_sum(&values, 1), 5);
+        values[2] = 7;
+        clear_all();
+        assert_eq!(suffix_sum(&values, 1), 9);
+    }
+
+    crate::memo! {
+        fn count_paths(graph: &HashMap<u32, Vec<u32>>; from: u32, to: u32) -> u64 {
+            if from == to {
+                return 1;
+            }
+            graph.get(&from).into_iter().flatten().map(|&next| count_paths(graph, next, to)).sum()
+        }
+    }
+
+    #[test]
+    fn test_hash_map_context() {
+        // Layers of 3 nodes all linked to the 3 nodes of the next layer
+        let layers = |num_layers: u32| -> HashMap<u32, Vec<u32>> {
+            (0..3 * (num_layers - 1))
+                .map(|node| (node, (0..3).map(|i| (node / 3 + 1) * 3 + i).collect()))
+                .collect()
+        };
+
+        let (small, large) = (layers(3), layers(30));
+        assert_eq!(count_paths(&small, 0, 6), 3);
+        assert_eq!(count_paths(&large, 0, 87), 3u64.pow(28));
+        assert_eq!(count_paths(&small, 0, 6), 3);
+
+        // Calling it from every node reuses the values of the previous calls
+        let total: u64 = (0..90).map(|node| count_paths(&large, node, 87)).sum();
+        // The target itself, then 3^k paths from each of the 3 nodes k + 1 layers before it
+        assert_eq!(total, 1 + (0..=28).map(|k| 3 * 3u64.pow(k)).sum::<u64>());
+        #[cfg(debug_assertions)]
+        {
+            let (_, stats) = stats()
+                .into_iter()
+                .find(|(name, _)| *name == "count_paths")
+                .unwrap();
+            // Each node is computed once per change of graph
+            assert!(stats.misses <= 2 * (7 + 90), "{stats:?}");
+        }
+    }
+
+    #[test]
+    fn test_clear_all() {
+        stairs(30);
+        let calls = |stats: Vec<(&str, MemoStats)>| {
+            stats
+                .iter()
+                .find(|(name, _)| *name == "stairs")
+                .map(|(_, s)| *s)
+        };
+
+        #[cfg(debug_assertions)]
+        {
+            let stats = calls(stats()).unwrap();
+            assert_eq!(stats.misses, 31);
+            assert_eq!(stats.hits, 2 * 27);
+            assert!(stats.hit_rate() > 0.5);
+        }
+
+        // The values are computed again after clearing
+        clear_all();
+        assert_eq!(calls(stats()), None);
+        stairs(3);
+        #[cfg(debug_assertions)]
+        assert_eq!(calls(stats()).unwrap().misses, 4);
+    }
+
+    #[test]
+    fn test_cache() {
+        let mut cache = MemoCache::new("test");
+        assert_eq!(cache.get(&1), None);
+        cache.insert(1, "one");
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.name(), "test");
+
+        clear_all();
+        assert_eq!(cache.get(&1), None);
+        assert!(cache.is_empty());
+        assert_eq!(MemoStats::default().hit_rate(), 0.0);
+    }
+}
@@ -0,0 +1,361 @@
+/// Number theory helpers: gcd and lcm, modular arithmetic and the Chinese Remainder Theorem.
+use std::fmt::Display;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Greatest common divisor, `gcd(0, 0) == 0`.
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, `lcm(a, 0) == 0`.
+///
+/// # Panics
+///
+/// Panics if the result doesn't fit in a `u64`.
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b))
+        .checked_mul(b)
+        .expect("attempt to compute a lcm with overflow")
+}
+
+/// Greatest common divisor of all the values, `0` if there are none.
+pub fn gcd_slice(values: &[u64]) -> u64 {
+    values.iter().fold(0, |acc, &value| gcd(acc, value))
+}
+
+/// Least common multiple of all the values, `1` if there are none.
+///
+/// # Panics
+///
+/// Panics if the result doesn't fit in a `u64`.
+pub fn lcm_slice(values: &[u64]) -> u64 {
+    values.iter().fold(1, |acc, &value| lcm(acc, value))
+}
+
+/// Extended Euclidean algorithm, returns `(g, x, y)` such that `a * x + b * y == g` with `g == gcd(a, b) >= 0`.
+///
+/// # Panics
+///
+/// Panics if the gcd is `2^63`, which doesn't fit in an `i64`. It only happens when one of `a` and `b` is `i64::MIN`
+/// and the other one is `0` or `i64::MIN`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = extended_gcd_i128(a as i128, b as i128);
+    let g = i64::try_from(g).expect("attempt to compute a gcd with overflow");
+    // The coefficients are at most |a / g| and |b / g| in absolute value, they fit
+    (g, x as i64, y as i64)
+}
+
+/// Same as [`extended_gcd`], wide enough for `u64` moduli.
+fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1i128, 0i128);
+    let (mut old_y, mut y) = (0i128, 1i128);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (old_r, old_x, old_y) = (-old_r, -old_x, -old_y);
+    }
+    (old_r, old_x, old_y)
+}
+
+/// Reduces `a` modulo `modulus` into `0..modulus`, negative values included.
+fn reduce(a: i128, modulus: u64) -> u64 {
+    a.rem_euclid(modulus as i128) as u64
+}
+
+/// Multiplies `a` and `b` modulo `modulus` without overflowing.
+pub fn mod_mul(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+/// Computes `base^exp mod modulus` by repeated squaring.
+///
+/// # Panics
+///
+/// Panics if `modulus` is `0`.
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut base = base % modulus;
+    let mut ret = 1 % modulus;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            ret = mod_mul(ret, base, modulus);
+        }
+        base = mod_mul(base, base, modulus);
+        exp >>= 1;
+    }
+
+    ret
+}
+
+/// Returns `x` in `0..modulus` such that `a * x ≡ 1 (mod modulus)`, or `None` if `a` and `modulus` aren't coprime.
+pub fn mod_inverse(a: i64, modulus: u64) -> Option<u64> {
+    inverse(reduce(a as i128, modulus), modulus)
+}
+
+fn inverse(a: u64, modulus: u64) -> Option<u64> {
+    let (g, x, _) = extended_gcd_i128(a as i128, modulus as i128);
+    (g == 1).then(|| reduce(x, modulus))
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)` given as `(residue, modulus)` pairs, the moduli don't
+/// need to be coprime.
+///
+/// Returns `(x, lcm)` where `x` is the smallest non-negative solution and every solution is `x` plus a multiple of the
+/// lcm of the moduli. Returns `None` if the congruences are incompatible or if the lcm doesn't fit in a `u64`.
+///
+/// # Panics
+///
+/// Panics if a modulus is `0`.
+pub fn crt(congruences: &[(i64, u64)]) -> Option<(u64, u64)> {
+    let (mut x, mut modulus) = (0u64, 1u64);
+
+    for &(residue, other) in congruences {
+        assert!(other != 0, "modulus must be positive");
+        let residue = reduce(residue as i128, other);
+
+        // Looking for k such that x + modulus * k ≡ residue (mod other)
+        let g = gcd(modulus, other);
+        let diff = residue as i128 - x as i128;
+        if diff % g as i128 != 0 {
+            return None;
+        }
+
+        let reduced_other = other / g;
+        let inverse = inverse(modulus / g % reduced_other, reduced_other)?;
+        let k = mod_mul(
+            reduce(diff / g as i128, reduced_other),
+            inverse,
+            reduced_other,
+        );
+
+        let new_modulus = modulus.checked_mul(reduced_other)?;
+        x = ((x as u128 + modulus as u128 * k as u128) % new_modulus as u128) as u64;
+        modulus = new_modulus;
+    }
+
+    Some((x, modulus))
+}
+
+/// An integer modulo `M`, always stored in `0..M`.
+///
+/// Operators wrap around `M`. Division multiplies by the modular inverse and panics if it doesn't exist, use
+/// [`ModInt::inv`] to check first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    /// Creates the class of `value` modulo `M`, negative values included.
+    pub fn new(value: i64) -> Self {
+        Self(reduce(value as i128, M))
+    }
+
+    /// Representative in `0..M`.
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(self, exp: u64) -> Self {
+        Self(mod_pow(self.0, exp, M))
+    }
+
+    /// Multiplicative inverse, `None` if the value isn't coprime with `M`.
+    pub fn inv(self) -> Option<Self> {
+        inverse(self.0, M).map(Self)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        Self(value % M)
+    }
+}
+
+impl<const M: u64> From<i64> for ModInt<M> {
+    fn from(value: i64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<const M: u64> Display for ModInt<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 + rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self((M - self.0) % M)
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(mod_mul(self.0, rhs.0, M))
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        let inverse = rhs
+            .inv()
+            .expect("attempt to divide by a value that isn't invertible");
+        self.mul(inverse)
+    }
+}
+
+macro_rules! impl_assign {
+    ($($trait:ident, $method:ident, $op:tt);*) => {
+        $(
+            impl<const M: u64> $trait for ModInt<M> {
+                fn $method(&mut self, rhs: Self) {
+                    *self = *self $op rhs;
+                }
+            }
+        )*
+    };
+}
+
+impl_assign!(AddAssign, add_assign, +; SubAssign, sub_assign, -; MulAssign, mul_assign, *; DivAssign, div_assign, /);
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self(0), Add::add)
+    }
+}
+
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::from(1u64), Mul::mul)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(gcd_slice(&[12, 18, 27]), 3);
+        assert_eq!(gcd_slice(&[]), 0);
+        assert_eq!(lcm_slice(&[2, 3, 4, 5]), 60);
+        assert_eq!(lcm_slice(&[]), 1);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240, 46), (-240, 46), (46, -240), (0, 5), (7, 0), (17, 13)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g as u64, gcd(a.unsigned_abs(), b.unsigned_abs()));
+            assert_eq!(a * x + b * y, g);
+        }
+
+        let (g, x, y) = extended_gcd(i64::MIN, 6);
+        assert_eq!((g, i64::MIN.wrapping_mul(x).wrapping_add(6 * y)), (2, 2));
+        assert_eq!(extended_gcd(i64::MIN + 1, 0), (i64::MAX, -1, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "gcd with overflow")]
+    fn test_extended_gcd_overflow() {
+        extended_gcd(i64::MIN, 0);
+    }
+
+    #[test]
+    fn test_mod_pow_inverse() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(3, 0, 7), 1);
+        assert_eq!(mod_pow(5, 3, 1), 0);
+        assert_eq!(mod_pow(u64::MAX - 1, 3, u64::MAX), u64::MAX - 1);
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(
+            crt(&[(0, 7), (-1, 13), (-4, 59), (-6, 31), (-7, 19)]),
+            Some((1068781, 3162341))
+        );
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(1, u64::MAX), (0, 2)]), None);
+    }
+
+    #[test]
+    fn test_mod_int() {
+        type M7 = ModInt<7>;
+
+        let a = M7::new(-3);
+        assert_eq!(a.value(), 4);
+        assert_eq!((a + M7::from(5u64)).value(), 2);
+        assert_eq!((a - M7::from(6u64)).value(), 5);
+        assert_eq!((a * a).value(), 2);
+        assert_eq!((M7::from(1u64) / a * a).value(), 1);
+        assert_eq!(a.pow(6).value(), 1);
+        assert_eq!(ModInt::<8>::new(4).inv(), None);
+
+        let mut b = M7::new(3);
+        b += a;
+        b *= M7::new(3);
+        b -= M7::new(1);
+        b /= M7::new(2);
+        assert_eq!(b.to_string(), "3");
+
+        assert_eq!((1..=6).map(M7::new).product::<M7>().value(), 6);
+        assert_eq!((1..=6).map(M7::new).sum::<M7>().value(), 0);
+
+        const BIG: u64 = u64::MAX - 58;
+        let big = ModInt::<BIG>::new(-1);
+        assert_eq!((big * big).value(), 1);
+        assert_eq!((big + big).value(), BIG - 2);
+        assert_eq!(
+            ModInt::<BIG>::new(2).inv().unwrap() * ModInt::new(2),
+            ModInt::new(1)
+        );
+    }
+}
@@ -0,0 +1,417 @@
+/// Graph searches over a successor closure, so they apply to grids and abstract state spaces alike.
+///
+/// Every search accepts several start nodes and an `is_goal` predicate that stops it early, pass `|_| false` to
+/// explore everything reachable.
+use std::cmp::{Ordering, Reverse};
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Nodes reached by a breadth-first search or Dijkstra's algorithm.
+#[derive(Debug, Clone)]
+pub struct Reached<N, C> {
+    /// Distance from the closest start node
+    distances: HashMap<N, C>,
+    /// Previous node on a shortest path, start nodes have none
+    parents: HashMap<N, N>,
+    /// Number of distinct shortest paths from any start node
+    path_counts: HashMap<N, u64>,
+    /// The first goal that was reached, if any
+    goal: Option<N>,
+}
+
+impl<N: Eq + Hash + Clone, C: Copy> Reached<N, C> {
+    fn new(starts: impl IntoIterator<Item = N>, zero: C) -> Self {
+        let mut reached = Self {
+            distances: HashMap::new(),
+            parents: HashMap::new(),
+            path_counts: HashMap::new(),
+            goal: None,
+        };
+        for start in starts {
+            reached.distances.insert(start.clone(), zero);
+            reached.path_counts.insert(start, 1);
+        }
+        reached
+    }
+
+    /// Returns the distance from the closest start node to `node`, or `None` if it wasn't reached.
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    /// Returns the first goal that was reached.
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+
+    /// Returns the distance to the goal.
+    pub fn goal_distance(&self) -> Option<C> {
+        self.distance(self.goal.as_ref()?)
+    }
+
+    /// Returns a shortest path to the goal, start and goal included.
+    pub fn goal_path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+
+    /// Returns a shortest path from a start node to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Returns the number of distinct shortest paths from any start node to `node`, `0` if it wasn't reached.
+    pub fn count_shortest_paths(&self, node: &N) -> u64 {
+        self.path_counts.get(node).copied().unwrap_or(0)
+    }
+
+    /// Iterates over every reached node and its distance, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&N, C)> {
+        self.distances
+            .iter()
+            .map(|(node, &distance)| (node, distance))
+    }
+
+    /// Records that `node` can be reached from `parent` with a path of length `distance`.
+    /// Returns `true` if it is the first time `node` is reached or if the path is strictly shorter.
+    fn relax(&mut self, parent: &N, node: N, distance: C) -> bool
+    where
+        C: Ord,
+    {
+        let parent_count = self.count_shortest_paths(parent);
+        match self.distances.entry(node.clone()) {
+            Entry::Occupied(mut entry) => match distance.cmp(entry.get()) {
+                Ordering::Less => {
+                    entry.insert(distance);
+                    self.parents.insert(node.clone(), parent.clone());
+                    self.path_counts.insert(node, parent_count);
+                    true
+                }
+                Ordering::Equal => {
+                    *self.path_counts.entry(node).or_default() += parent_count;
+                    false
+                }
+                Ordering::Greater => false,
+            },
+            Entry::Vacant(entry) => {
+                entry.insert(distance);
+                self.parents.insert(node.clone(), parent.clone());
+                self.path_counts.insert(node, parent_count);
+                true
+            }
+        }
+    }
+}
+
+/// Breadth-first search, every edge has length 1.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Reached<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut reached = Reached::new(starts, 0);
+    let mut queue: VecDeque<N> = reached.distances.keys().cloned().collect();
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            reached.goal = Some(node);
+            break;
+        }
+
+        let distance = reached.distances[&node] + 1;
+        for successor in successors(&node) {
+            if reached.relax(&node, successor.clone(), distance) {
+                queue.push_back(successor);
+            }
+        }
+    }
+
+    reached
+}
+
+/// Depth-first search, returns the path to the first goal found, which is not necessarily the shortest one.
+pub fn dfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = HashSet::new();
+
+    for start in starts {
+        if !visited.insert(start.clone()) {
+            continue;
+        }
+
+        // Stack of the successors left to explore at each depth, the current path is made of the last node of each level
+        let mut path = vec![start.clone()];
+        let mut stack = vec![successors(&start).into_iter()];
+        if is_goal(&start) {
+            return Some(path);
+        }
+
+        while let Some(pending) = stack.last_mut() {
+            match pending.next() {
+                Some(node) => {
+                    if visited.insert(node.clone()) {
+                        path.push(node.clone());
+                        if is_goal(&node) {
+                            return Some(path);
+                        }
+                        stack.push(successors(&node).into_iter());
+                    }
+                }
+                None => {
+                    stack.pop();
+                    path.pop();
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Heap entry ordered by increasing `priority` only.
+struct Queued<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.cmp(&other.priority)
+    }
+}
+
+/// Dijkstra's algorithm, `successors` returns `(node, cost)` pairs. `C::default()` must be zero.
+/// Shortest path counts are exact when every cost is positive.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Reached<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut reached = Reached::new(starts, C::default());
+    let mut heap: BinaryHeap<Reverse<Queued<N, C>>> = reached
+        .distances
+        .keys()
+        .map(|node| {
+            Reverse(Queued {
+                priority: C::default(),
+                cost: C::default(),
+                node: node.clone(),
+            })
+        })
+        .collect();
+
+    while let Some(Reverse(Queued { cost, node, .. })) = heap.pop() {
+        // Skip outdated entries
+        if cost > reached.distances[&node] {
+            continue;
+        }
+
+        if is_goal(&node) {
+            reached.goal = Some(node);
+            break;
+        }
+
+        for (successor, edge_cost) in successors(&node) {
+            let successor_cost = cost + edge_cost;
+            if reached.relax(&node, successor.clone(), successor_cost) {
+                heap.push(Reverse(Queued {
+                    priority: successor_cost,
+                    cost: successor_cost,
+                    node: successor,
+                }));
+            }
+        }
+    }
+
+    reached
+}
+
+/// A* search, returns a cheapest path to a goal, start and goal included, and its cost.
+/// `heuristic` must never overestimate the remaining cost to the closest goal. `C::default()` must be zero.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut reached = Reached::new(starts, C::default());
+    let mut heap: BinaryHeap<Reverse<Queued<N, C>>> = reached
+        .distances
+        .keys()
+        .map(|node| {
+            Reverse(Queued {
+                priority: heuristic(node),
+                cost: C::default(),
+                node: node.clone(),
+            })
+        })
+        .collect();
+
+    while let Some(Reverse(Queued { cost, node, .. })) = heap.pop() {
+        if cost > reached.distances[&node] {
+            continue;
+        }
+
+        if is_goal(&node) {
+            return Some((reached.path_to(&node)?, cost));
+        }
+
+        for (successor, edge_cost) in successors(&node) {
+            let successor_cost = cost + edge_cost;
+            if reached.relax(&node, successor.clone(), successor_cost) {
+                heap.push(Reverse(Queued {
+                    priority: successor_cost + heuristic(&successor),
+                    cost: successor_cost,
+                    node: successor,
+                }));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 3 -> 4
+    //  \-> 2 -/
+    // 5 is isolated
+    fn diamond(&node: &u32) -> Vec<u32> {
+        match node {
+            0 => vec![1, 2],
+            1 | 2 => vec![3],
+            3 => vec![4],
+            _ => vec![],
+        }
+    }
+
+    // Same graph, 0 -> 2 is cheaper and 2 -> 3 more expensive
+    fn weighted(&node: &u32) -> Vec<(u32, u64)> {
+        match node {
+            0 => vec![(1, 2), (2, 1)],
+            1 => vec![(3, 2)],
+            2 => vec![(3, 3)],
+            3 => vec![(4, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let reached = bfs([0], diamond, |_| false);
+        assert_eq!(reached.distance(&4), Some(3));
+        assert_eq!(reached.distance(&5), None);
+        assert_eq!(reached.path_to(&4), Some(vec![0, 1, 3, 4]));
+        assert_eq!(reached.count_shortest_paths(&4), 2);
+        assert_eq!(reached.count_shortest_paths(&1), 1);
+        assert_eq!(reached.goal(), None);
+
+        let reached = bfs([0], diamond, |&node| node == 3);
+        assert_eq!(reached.goal_distance(), Some(2));
+        assert_eq!(reached.distance(&4), None);
+    }
+
+    #[test]
+    fn test_multi_source() {
+        let reached = bfs([2, 1], diamond, |&node| node == 4);
+        assert_eq!(reached.goal_distance(), Some(2));
+        assert_eq!(reached.count_shortest_paths(&4), 2);
+        assert_eq!(reached.goal_path().unwrap().len(), 3);
+
+        let reached = dijkstra([1, 2], weighted, |_| false);
+        assert_eq!(reached.distance(&3), Some(2));
+        assert_eq!(reached.path_to(&4), Some(vec![1, 3, 4]));
+    }
+
+    #[test]
+    fn test_dfs() {
+        assert_eq!(dfs([0], diamond, |&node| node == 4), Some(vec![0, 1, 3, 4]));
+        assert_eq!(dfs([0], diamond, |&node| node == 0), Some(vec![0]));
+        assert_eq!(dfs([0], diamond, |&node| node == 5), None);
+        assert_eq!(dfs([5, 2], diamond, |&node| node == 4), Some(vec![2, 3, 4]));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let reached = dijkstra([0], weighted, |_| false);
+        assert_eq!(reached.distance(&3), Some(4));
+        assert_eq!(reached.distance(&4), Some(5));
+        assert_eq!(reached.count_shortest_paths(&4), 2);
+
+        let reached = dijkstra([0], weighted, |&node| node == 2);
+        assert_eq!(reached.goal_path(), Some(vec![0, 2]));
+        assert_eq!(reached.goal_distance(), Some(1));
+    }
+
+    #[test]
+    fn test_astar() {
+        // Shortest path on a 5x5 grid with a wall on column 2, except on the last line
+        let wall = |(i, j): (i32, i32)| j == 2 && i < 4;
+        let successors = |&(i, j): &(i32, i32)| {
+            [(i - 1, j), (i + 1, j), (i, j - 1), (i, j + 1)]
+                .into_iter()
+                .filter(|&(k, l)| (0..5).contains(&k) && (0..5).contains(&l) && !wall((k, l)))
+                .map(|node| (node, 1))
+                .collect::<Vec<_>>()
+        };
+        let manhattan = |&(i, j): &(i32, i32)| (i.abs_diff(0) + j.abs_diff(4)) as u64;
+
+        let (path, cost) = astar([(0, 0)], successors, manhattan, |&node| node == (0, 4)).unwrap();
+        assert_eq!(cost, 12);
+        assert_eq!(path.len(), 13);
+        assert!(path.contains(&(4, 2)));
+
+        assert_eq!(
+            astar([(0, 0)], successors, manhattan, |&node| node == (1, 2)),
+            None
+        );
+    }
+}
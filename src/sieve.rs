@@ -0,0 +1,369 @@
+/// Prime sieve that grows on demand, with factorization helpers built on smallest prime factors.
+use std::mem;
+
+use crate::number_theory::{mod_mul, mod_pow};
+
+/// Largest limit the sieve grows to on its own to factorize a number, about 40 MB. Larger factors are found by trial
+/// division by odd numbers.
+const MAX_FACTORIZATION_LIMIT: u64 = 10_000_000;
+
+/// Linear sieve of the smallest prime factor of every integer up to a limit.
+///
+/// Queries above the limit grow the sieve, at least doubling it so that the cost is amortized. Numbers too large to be
+/// sieved directly are factorized by trial division, which only grows the sieve up to their square root and at most
+/// to 10^7, and tested for primality with Miller–Rabin, which doesn't grow it.
+#[derive(Debug, Clone, Default)]
+pub struct Sieve {
+    /// Smallest prime factor of every index, `0` for `0` and `1`
+    smallest_factors: Vec<u32>,
+    primes: Vec<u64>,
+}
+
+impl Sieve {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a sieve already covering `0..=limit`.
+    pub fn with_limit(limit: u64) -> Self {
+        let mut sieve = Self::new();
+        sieve.grow(limit);
+        sieve
+    }
+
+    /// Largest integer covered by the sieve.
+    pub fn limit(&self) -> u64 {
+        self.smallest_factors.len().saturating_sub(1) as u64
+    }
+
+    /// Makes sure the sieve covers `0..=limit`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` doesn't fit in a `u32`.
+    pub fn grow(&mut self, limit: u64) {
+        if !self.smallest_factors.is_empty() && limit <= self.limit() {
+            return;
+        }
+
+        assert!(
+            limit <= u32::MAX as u64,
+            "sieve limit {limit} doesn't fit in a u32"
+        );
+
+        // Growth is amortized by at least doubling, up to what fits in a u32
+        let limit = limit.max(2 * self.limit()).max(64).min(u32::MAX as u64);
+        let limit = usize::try_from(limit).expect("sieve limit too large");
+        let mut smallest_factors = vec![0u32; limit + 1];
+        let mut primes = mem::take(&mut self.primes);
+        primes.clear();
+
+        for n in 2..=limit {
+            if smallest_factors[n] == 0 {
+                smallest_factors[n] = n as u32;
+                primes.push(n as u64);
+            }
+
+            // Every composite is only marked once, by its smallest prime factor
+            for &p in &primes {
+                let multiple = n * p as usize;
+                if p > smallest_factors[n] as u64 || multiple > limit {
+                    break;
+                }
+                smallest_factors[multiple] = p as u32;
+            }
+        }
+
+        self.smallest_factors = smallest_factors;
+        self.primes = primes;
+    }
+
+    /// Primes found so far, in increasing order.
+    pub fn primes(&self) -> &[u64] {
+        &self.primes
+    }
+
+    /// Primes smaller than or equal to `n`, in increasing order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` doesn't fit in a `u32`.
+    pub fn primes_up_to(&mut self, n: u64) -> &[u64] {
+        self.grow(n);
+        let end = self.primes.partition_point(|&p| p <= n);
+        &self.primes[..end]
+    }
+
+    pub fn is_prime(&mut self, n: u64) -> bool {
+        if n < 2 {
+            false
+        } else if n > self.limit() {
+            miller_rabin(n)
+        } else {
+            self.smallest_factors[n as usize] as u64 == n
+        }
+    }
+
+    /// Returns `None` for `0` and `1`. Slow if `n` has no factor below 10^7 but a large one, see [`Sieve::factorize`].
+    pub fn smallest_prime_factor(&mut self, n: u64) -> Option<u64> {
+        if n < 2 {
+            return None;
+        }
+        self.factorize(n).first().map(|&(p, _)| p)
+    }
+
+    /// Returns the prime factors of `n` with their exponents, in increasing order. `1` has no factors.
+    ///
+    /// Above the limit of the sieve, `n` is divided by the primes up to its square root, growing the sieve up to 10^7,
+    /// and then by odd numbers. Numbers with two prime factors above 10^7 take up to `n.isqrt() / 2` divisions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    pub fn factorize(&mut self, mut n: u64) -> Vec<(u64, u32)> {
+        assert!(n > 0, "0 can't be factorized");
+        let mut ret: Vec<(u64, u32)> = vec![];
+
+        if n > self.limit() && n.isqrt() > self.limit() && self.limit() < MAX_FACTORIZATION_LIMIT {
+            self.grow(n.isqrt().min(MAX_FACTORIZATION_LIMIT));
+        }
+
+        if n > self.limit() {
+            for &p in &self.primes {
+                if p * p > n {
+                    break;
+                }
+                let mut exponent = 0;
+                while n.is_multiple_of(p) {
+                    n /= p;
+                    exponent += 1;
+                }
+                if exponent > 0 {
+                    ret.push((p, exponent));
+                }
+            }
+
+            // Past the sieve, odd numbers only divide what's left if they are prime
+            let mut d = (self.limit() + 1) | 1;
+            while d <= n / d {
+                let mut exponent = 0;
+                while n.is_multiple_of(d) {
+                    n /= d;
+                    exponent += 1;
+                }
+                if exponent > 0 {
+                    ret.push((d, exponent));
+                }
+                d += 2;
+            }
+
+            // What's left has no factor below its square root
+            if n > 1 {
+                ret.push((n, 1));
+            }
+            return ret;
+        }
+
+        while n > 1 {
+            let p = self.smallest_factors[n as usize] as u64;
+            n /= p;
+            match ret.last_mut() {
+                Some((last, exponent)) if *last == p => *exponent += 1,
+                _ => ret.push((p, 1)),
+            }
+        }
+
+        ret
+    }
+
+    /// Returns the divisors of `n` in increasing order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    pub fn divisors(&mut self, n: u64) -> Vec<u64> {
+        let mut ret = vec![1];
+
+        for (p, exponent) in self.factorize(n) {
+            let len = ret.len();
+            let mut power = 1;
+            for _ in 0..exponent {
+                power *= p;
+                for i in 0..len {
+                    ret.push(ret[i] * power);
+                }
+            }
+        }
+
+        ret.sort_unstable();
+        ret
+    }
+
+    /// Euler's totient: number of integers in `1..=n` coprime with `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    pub fn totient(&mut self, n: u64) -> u64 {
+        self.factorize(n)
+            .into_iter()
+            .fold(n, |acc, (p, _)| acc / p * (p - 1))
+    }
+
+    /// Möbius function: `0` if `n` has a squared prime factor, otherwise `-1` to the power of its number of prime
+    /// factors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    pub fn mobius(&mut self, n: u64) -> i32 {
+        let factors = self.factorize(n);
+        if factors.iter().any(|&(_, exponent)| exponent > 1) {
+            0
+        } else if factors.len().is_multiple_of(2) {
+            1
+        } else {
+            -1
+        }
+    }
+}
+
+/// Deterministic Miller–Rabin primality test, these bases are enough for every `u64`.
+fn miller_rabin(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    for p in BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    // n - 1 = odd * 2^zeros
+    let zeros = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> zeros;
+
+    BASES.iter().all(|&base| {
+        let mut x = mod_pow(base, odd, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..zeros {
+            x = mod_mul(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::gcd;
+
+    fn brute_factorize(mut n: u64) -> Vec<(u64, u32)> {
+        let mut ret = vec![];
+        let mut p = 2;
+        while n > 1 {
+            let mut exponent = 0;
+            while n.is_multiple_of(p) {
+                n /= p;
+                exponent += 1;
+            }
+            if exponent > 0 {
+                ret.push((p, exponent));
+            }
+            p += 1;
+        }
+        ret
+    }
+
+    #[test]
+    fn test_primes() {
+        let mut sieve = Sieve::new();
+        assert_eq!(sieve.primes_up_to(20), [2, 3, 5, 7, 11, 13, 17, 19]);
+        assert!(sieve.primes_up_to(1).is_empty());
+        assert_eq!(sieve.primes_up_to(1000).len(), 168);
+        assert!(sieve.limit() >= 1000);
+
+        assert!(!sieve.is_prime(0));
+        assert!(!sieve.is_prime(1));
+        assert!(sieve.is_prime(997));
+        assert!(sieve.is_prime(1_000_000_007));
+        assert!(!sieve.is_prime(1_000_000_007 * 3));
+        assert_eq!(sieve.smallest_prime_factor(91), Some(7));
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        assert!(!Sieve::new().is_prime(0));
+
+        // Miller-Rabin doesn't grow the sieve
+        let mut sieve = Sieve::with_limit(100);
+        assert!(sieve.is_prime(1_000_000_000_000_000_009));
+        assert!(sieve.is_prime(u64::MAX - 58));
+        assert!(!sieve.is_prime(1_000_000_007 * 998_244_353));
+        assert!(!sieve.is_prime(u64::MAX));
+        // Strong pseudoprime to the bases 2, 3, 5 and 7
+        assert!(!sieve.is_prime(3_215_031_751));
+        assert_eq!(sieve.limit(), 100);
+
+        let primes = Sieve::with_limit(10_000).primes().to_vec();
+        assert!((0..10_000).all(|n| miller_rabin(n) == primes.contains(&n)));
+
+        // Two prime factors above the largest limit the sieve grows to
+        let mut sieve = Sieve::new();
+        assert_eq!(
+            sieve.factorize(10_000_019 * 10_000_079 * 4),
+            [(2, 2), (10_000_019, 1), (10_000_079, 1)]
+        );
+        assert!(sieve.limit() <= 2 * MAX_FACTORIZATION_LIMIT);
+        assert_eq!(
+            sieve.smallest_prime_factor(10_000_079 * 10_000_079),
+            Some(10_000_079)
+        );
+    }
+
+    #[test]
+    fn test_factorize() {
+        let mut sieve = Sieve::with_limit(100);
+        for n in 1..2000 {
+            assert_eq!(sieve.factorize(n), brute_factorize(n), "{n}");
+        }
+
+        // Above the sieve limit, by trial division
+        let mut sieve = Sieve::with_limit(100);
+        assert_eq!(
+            sieve.factorize(600851475143),
+            [(71, 1), (839, 1), (1471, 1), (6857, 1)]
+        );
+        assert_eq!(sieve.factorize(1 << 40), [(2, 40)]);
+        assert!(sieve.limit() < 1 << 40);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in a u32")]
+    fn test_limit_too_large() {
+        Sieve::new().primes_up_to(u32::MAX as u64 + 1);
+    }
+
+    #[test]
+    fn test_divisor_functions() {
+        let mut sieve = Sieve::new();
+        assert_eq!(sieve.divisors(1), [1]);
+        assert_eq!(sieve.divisors(12), [1, 2, 3, 4, 6, 12]);
+        assert_eq!(sieve.divisors(49), [1, 7, 49]);
+
+        for n in 1..500 {
+            let coprimes = (1..=n).filter(|&k| gcd(k, n) == 1).count() as u64;
+            assert_eq!(sieve.totient(n), coprimes);
+        }
+
+        let mobius: Vec<i32> = (1..=12).map(|n| sieve.mobius(n)).collect();
+        assert_eq!(mobius, [1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0]);
+    }
+}
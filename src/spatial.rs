@@ -0,0 +1,224 @@
+/// Spatial index over integer points, used to find close points without comparing every pair.
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Squared euclidean distance between two points.
+pub fn squared_distance<const D: usize>(a: &[i64; D], b: &[i64; D]) -> u64 {
+    a.iter().zip(b).map(|(x, y)| x.abs_diff(*y).pow(2)).sum()
+}
+
+/// A k-d tree over `D`-dimensional points. Points are referred to by their index in the list given to [`KdTree::new`].
+#[derive(Debug, Clone)]
+pub struct KdTree<const D: usize> {
+    points: Vec<[i64; D]>,
+    /// Point indices laid out as an implicit tree: the median of each range is its root, split along `depth % D`
+    order: Vec<usize>,
+}
+
+impl<const D: usize> KdTree<D> {
+    pub fn new(points: Vec<[i64; D]>) -> Self {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        build(&points, &mut order, 0);
+        Self { points, order }
+    }
+
+    pub fn points(&self) -> &[[i64; D]] {
+        &self.points
+    }
+
+    /// Returns the `k` points closest to `target` among the ones whose index matches `filter`, as
+    /// `(squared distance, index)` pairs sorted by increasing distance, then by index.
+    pub fn nearest(
+        &self,
+        target: &[i64; D],
+        k: usize,
+        filter: impl Fn(usize) -> bool,
+    ) -> Vec<(u64, usize)> {
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(&self.order, 0, target, k, &filter, &mut best);
+        }
+        best.into_sorted_vec()
+    }
+
+    fn search(
+        &self,
+        order: &[usize],
+        depth: usize,
+        target: &[i64; D],
+        k: usize,
+        filter: &impl Fn(usize) -> bool,
+        best: &mut BinaryHeap<(u64, usize)>,
+    ) {
+        if order.is_empty() {
+            return;
+        }
+
+        let mid = order.len() / 2;
+        let index = order[mid];
+        let point = &self.points[index];
+
+        if filter(index) {
+            best.push((squared_distance(point, target), index));
+            if best.len() > k {
+                best.pop();
+            }
+        }
+
+        let axis = depth % D;
+        let (near, far) = if target[axis] < point[axis] {
+            (&order[..mid], &order[mid + 1..])
+        } else {
+            (&order[mid + 1..], &order[..mid])
+        };
+
+        self.search(near, depth + 1, target, k, filter, best);
+
+        // Points on the other side are at least as far as the splitting plane
+        let plane_distance = target[axis].abs_diff(point[axis]).pow(2);
+        if best.len() < k
+            || best
+                .peek()
+                .is_some_and(|&(worst, _)| plane_distance <= worst)
+        {
+            self.search(far, depth + 1, target, k, filter, best);
+        }
+    }
+
+    /// Lazily iterates over every pair of points `(i, j)` with `i < j`, by increasing distance, then by `i` and `j`.
+    /// Yields `(squared distance, i, j)`.
+    pub fn closest_pairs(&self) -> ClosestPairs<'_, D> {
+        let mut pairs = ClosestPairs {
+            tree: self,
+            neighbors: vec![Neighbors::default(); self.points.len()],
+            heap: BinaryHeap::new(),
+        };
+        for i in 0..self.points.len() {
+            pairs.push_next(i);
+        }
+        pairs
+    }
+}
+
+fn build<const D: usize>(points: &[[i64; D]], order: &mut [usize], depth: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+
+    let mid = order.len() / 2;
+    let axis = depth % D;
+    order.select_nth_unstable_by_key(mid, |&index| points[index][axis]);
+
+    let (left, right) = order.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+/// Nearest neighbors of a point with a larger index, fetched in batches of doubling size.
+#[derive(Debug, Clone, Default)]
+struct Neighbors {
+    batch: Vec<(u64, usize)>,
+    /// Number of neighbors already pushed to the heap
+    next: usize,
+    /// The last query returned less neighbors than requested, so there are none left
+    exhausted: bool,
+}
+
+/// Iterator returned by [`KdTree::closest_pairs`].
+///
+/// Every point keeps its next unvisited neighbor in a heap, so only the pairs that are actually consumed get computed.
+pub struct ClosestPairs<'a, const D: usize> {
+    tree: &'a KdTree<D>,
+    neighbors: Vec<Neighbors>,
+    heap: BinaryHeap<Reverse<(u64, usize, usize)>>,
+}
+
+impl<const D: usize> ClosestPairs<'_, D> {
+    /// Pushes the next pair of `i` to the heap, querying more neighbors if the current batch is exhausted.
+    fn push_next(&mut self, i: usize) {
+        let neighbors = &mut self.neighbors[i];
+
+        if neighbors.next == neighbors.batch.len() {
+            if neighbors.exhausted {
+                return;
+            }
+
+            // Batches are sorted by distance then index, so the new batch starts with the previous one
+            let requested = (2 * neighbors.batch.len()).max(2);
+            neighbors.batch = self
+                .tree
+                .nearest(&self.tree.points[i], requested, |index| index > i);
+            neighbors.exhausted = neighbors.batch.len() < requested;
+        }
+
+        if let Some(&(distance, j)) = neighbors.batch.get(neighbors.next) {
+            neighbors.next += 1;
+            self.heap.push(Reverse((distance, i, j)));
+        }
+    }
+}
+
+impl<const D: usize> Iterator for ClosestPairs<'_, D> {
+    type Item = (u64, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((distance, i, j)) = self.heap.pop()?;
+        self.push_next(i);
+        Some((distance, i, j))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    fn random_points(count: usize) -> Vec<[i64; 3]> {
+        // Small linear congruential generator, coordinates are kept small to get many ties
+        let mut state = 12345u64;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % 50) as i64 - 25
+        };
+        (0..count).map(|_| [next(), next(), next()]).collect()
+    }
+
+    #[test]
+    fn test_nearest() {
+        let tree = KdTree::new(vec![[0, 0], [5, 5], [1, 1], [-2, 0], [3, 3]]);
+        assert_eq!(
+            tree.nearest(&[0, 0], 3, |_| true),
+            vec![(0, 0), (2, 2), (4, 3)]
+        );
+        assert_eq!(
+            tree.nearest(&[4, 4], 2, |index| index != 1),
+            vec![(2, 4), (18, 2)]
+        );
+        assert_eq!(tree.nearest(&[4, 4], 0, |_| true), vec![]);
+    }
+
+    #[test]
+    fn test_closest_pairs() {
+        let points = random_points(200);
+        let tree = KdTree::new(points.clone());
+
+        let expected = (0..points.len())
+            .tuple_combinations()
+            .map(|(i, j)| (squared_distance(&points[i], &points[j]), i, j))
+            .sorted()
+            .collect_vec();
+
+        assert_eq!(tree.closest_pairs().take(50).collect_vec(), expected[..50]);
+        assert_eq!(tree.closest_pairs().collect_vec(), expected);
+    }
+
+    #[test]
+    fn test_degenerate() {
+        assert_eq!(KdTree::<3>::new(vec![]).closest_pairs().count(), 0);
+        assert_eq!(KdTree::new(vec![[1, 2, 3]]).closest_pairs().count(), 0);
+        assert_eq!(KdTree::new(vec![[1, 1, 1]; 4]).closest_pairs().count(), 6);
+    }
+}
@@ -0,0 +1,329 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use crate::template::{
+    module_template,
+    usage::{COMMANDS, CommandUsage, OptionUsage, ValueKind},
+};
+
+/// A shell the `completions` command can generate a script for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = ShellFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err(ShellFromStrError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a [`Shell`].
+#[derive(Debug)]
+pub struct ShellFromStrError;
+
+impl Error for ShellFromStrError {}
+
+impl Display for ShellFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting one of `bash`, `zsh` or `fish`")
+    }
+}
+
+/// Shell functions listing the values that depend on the state of the repository, user templates included.
+/// They print whitespace-separated words and are valid in both bash and zsh.
+const POSIX_HELPERS: &str = r#"_aoc_days() {
+    printf '%02d ' $(seq 1 25)
+}
+
+_aoc_scaffolded_days() {
+    local file
+    for file in src/bin/[0-9][0-9].rs; do
+        [ -e "$file" ] && basename "$file" .rs
+    done
+}
+
+_aoc_templates() {
+    echo "%BUILTIN_TEMPLATES%"
+    local file
+    for file in data/templates/*.txt; do
+        [ -e "$file" ] && basename "$file" .txt
+    done
+}
+"#;
+
+const FISH_HELPERS: &str = r#"function __aoc_days
+    printf '%02d\n' (seq 1 25)
+end
+
+function __aoc_scaffolded_days
+    for file in src/bin/[0-9][0-9].rs
+        basename $file .rs
+    end
+end
+
+function __aoc_templates
+    string split ' ' -- '%BUILTIN_TEMPLATES%'
+    for file in data/templates/*.txt
+        basename $file .txt
+    end
+end
+"#;
+
+fn builtin_templates() -> String {
+    module_template::builtin_names()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `cargo help` is a builtin, so the `help` command can't be reached through a cargo alias.
+fn completable_commands() -> impl Iterator<Item = &'static CommandUsage> {
+    COMMANDS.iter().filter(|command| command.name != "help")
+}
+
+/// Options that can be completed, `--flag=value` forms are left out.
+fn completable_options(command: &CommandUsage) -> impl Iterator<Item = &OptionUsage> {
+    command
+        .options
+        .iter()
+        .filter(|option| !option.flag.ends_with('='))
+}
+
+/// Shell expression expanding to the candidate values of `kind` in bash and zsh.
+fn posix_values(kind: ValueKind) -> String {
+    match kind {
+        ValueKind::Day => "$(_aoc_days)".into(),
+        ValueKind::ScaffoldedDay => "$(_aoc_scaffolded_days)".into(),
+        ValueKind::Part => "1 2".into(),
+        ValueKind::Template => "$(_aoc_templates)".into(),
+        ValueKind::Shell => "bash zsh fish".into(),
+        ValueKind::Command => completable_commands()
+            .map(|command| command.name)
+            .collect::<Vec<_>>()
+            .join(" "),
+        ValueKind::Other => String::new(),
+    }
+}
+
+/// Fish expression expanding to the candidate values of `kind`.
+fn fish_values(kind: ValueKind) -> String {
+    match kind {
+        ValueKind::Day => "(__aoc_days)".into(),
+        ValueKind::ScaffoldedDay => "(__aoc_scaffolded_days)".into(),
+        ValueKind::Template => "(__aoc_templates)".into(),
+        kind => posix_values(kind),
+    }
+}
+
+/// Words completed after the subcommand when the previous word isn't an option expecting a value.
+fn posix_words(command: &CommandUsage) -> String {
+    completable_options(command)
+        .map(|option| option.flag.to_string())
+        .chain(command.args.iter().map(|arg| posix_values(arg.kind)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `case` arms mapping options that expect a value to the candidate values.
+fn posix_value_arms(command: &CommandUsage, indent: &str) -> String {
+    completable_options(command)
+        .filter_map(|option| {
+            let (_, kind) = option.value?;
+            Some(format!(
+                "{indent}{}) words=\"{}\" ;;\n",
+                option.flag,
+                posix_values(kind)
+            ))
+        })
+        .collect()
+}
+
+fn bash() -> String {
+    let mut script = String::from(
+        "# bash completion for the Advent of Code cargo aliases, generated by `cargo completions bash`.\n\n",
+    );
+    script.push_str(&POSIX_HELPERS.replace("%BUILTIN_TEMPLATES%", &builtin_templates()));
+    script.push_str(
+        r#"
+# Completes cargo's own subcommands with the completion shipped with rustup.
+_aoc_cargo_fallback() {
+    if ! declare -F _cargo >/dev/null; then
+        source "$(rustc --print sysroot 2>/dev/null)/etc/bash_completion.d/cargo" 2>/dev/null
+        complete -F _aoc_cargo -o bashdefault -o default cargo
+    fi
+    declare -F _cargo >/dev/null && _cargo "$@"
+}
+
+_aoc_cargo() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local words
+
+    if [[ $COMP_CWORD -lt 2 ]]; then
+        _aoc_cargo_fallback "$@"
+        return
+    fi
+
+    case "${COMP_WORDS[1]}" in
+"#,
+    );
+
+    for command in completable_commands() {
+        script.push_str(&format!(
+            "        {})\n            case \"$prev\" in\n{}                *) words=\"{}\" ;;\n            esac\n            ;;\n",
+            command.name,
+            posix_value_arms(command, "                "),
+            posix_words(command)
+        ));
+    }
+
+    script.push_str(
+        r#"        *)
+            _aoc_cargo_fallback "$@"
+            return
+            ;;
+    esac
+
+    COMPREPLY=($(compgen -W "$words" -- "$cur"))
+}
+
+complete -F _aoc_cargo -o bashdefault -o default cargo
+"#,
+    );
+
+    script
+}
+
+fn zsh() -> String {
+    let mut script = String::from(
+        "# zsh completion for the Advent of Code cargo aliases, generated by `cargo completions zsh`.\n\n",
+    );
+    script.push_str(&POSIX_HELPERS.replace("%BUILTIN_TEMPLATES%", &builtin_templates()));
+    script.push_str(
+        r#"
+_aoc_cargo() {
+    local words_
+    if (( CURRENT > 2 )); then
+        case ${words[2]} in
+"#,
+    );
+
+    for command in completable_commands() {
+        script.push_str(&format!(
+            "            {})\n                case ${{words[CURRENT-1]}} in\n{}                    *) words_=\"{}\" ;;\n                esac\n                compadd -- ${{=words_}}\n                return\n                ;;\n",
+            command.name,
+            posix_value_arms(command, "                    ").replace("words=", "words_="),
+            posix_words(command)
+        ));
+    }
+
+    script.push_str(
+        r#"        esac
+    fi
+
+    # Completes cargo's own subcommands with the completion shipped with rustup.
+    (( $+functions[_cargo] )) || autoload -Uz _cargo
+    _cargo "$@"
+}
+
+compdef _aoc_cargo cargo
+"#,
+    );
+
+    script
+}
+
+fn fish() -> String {
+    let mut script = String::from(
+        "# fish completion for the Advent of Code cargo aliases, generated by `cargo completions fish`.\n\n",
+    );
+    script.push_str(&FISH_HELPERS.replace("%BUILTIN_TEMPLATES%", &builtin_templates()));
+    script.push('\n');
+
+    for command in completable_commands() {
+        script.push_str(&format!(
+            "complete -c cargo -n __fish_use_subcommand -f -a {} -d '{}'\n",
+            command.name,
+            command.about.replace('\'', "\\'")
+        ));
+    }
+
+    for command in completable_commands() {
+        let condition = format!("__fish_seen_subcommand_from {}", command.name);
+        script.push('\n');
+
+        for arg in command.args {
+            script.push_str(&format!(
+                "complete -c cargo -n '{condition}' -f -a '{}' -d '{}'\n",
+                fish_values(arg.kind),
+                arg.about
+            ));
+        }
+
+        for option in completable_options(command) {
+            let long = option.flag.trim_start_matches("--");
+            let about = option.about.replace('\'', "\\'");
+            match option.value {
+                Some((_, kind)) => script.push_str(&format!(
+                    "complete -c cargo -n '{condition}' -l {long} -x -a '{}' -d '{about}'\n",
+                    fish_values(kind)
+                )),
+                None => script.push_str(&format!(
+                    "complete -c cargo -n '{condition}' -l {long} -d '{about}'\n"
+                )),
+            }
+        }
+    }
+
+    script
+}
+
+pub fn handle(shell: Shell) {
+    let script = match shell {
+        Shell::Bash => bash(),
+        Shell::Zsh => zsh(),
+        Shell::Fish => fish(),
+    };
+
+    print!("{script}");
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{bash, fish, zsh};
+
+    #[test]
+    fn bash_completes_scaffolded_days() {
+        let script = bash();
+        assert!(script.contains("        solve)\n"));
+        assert!(script.contains("--submit) words=\"1 2\" ;;"));
+        assert!(script.contains("$(_aoc_scaffolded_days)"));
+        assert!(!script.contains("%BUILTIN_TEMPLATES%"));
+    }
+
+    #[test]
+    fn zsh_completes_scaffolded_days() {
+        let script = zsh();
+        assert!(script.contains("            solve)\n"));
+        assert!(script.contains("--template) words_=\"$(_aoc_templates)\" ;;"));
+        assert!(script.contains("compdef _aoc_cargo cargo"));
+    }
+
+    #[test]
+    fn fish_completes_scaffolded_days() {
+        let script = fish();
+        assert!(script.contains(
+            "complete -c cargo -n '__fish_seen_subcommand_from solve' -f -a '(__aoc_scaffolded_days)'"
+        ));
+        assert!(script.contains("-l submit -x -a '1 2'"));
+        assert!(!script.contains("-a help"));
+    }
+}
@@ -1,7 +1,18 @@
 use crate::template::{Day, aoc_cli};
+use std::path::Path;
 use std::process;
 
-pub fn handle(day: Day) {
+/// Downloads the input and the puzzle description of `day`. Without `overwrite`, nothing is downloaded if the input
+/// file already exists.
+pub fn handle(day: Day, overwrite: bool) {
+    let input_path = aoc_cli::get_input_path(day);
+    if !overwrite && Path::new(&input_path).exists() {
+        println!(
+            "Skipped downloading the input: \"{input_path}\" already exists, use `--overwrite=input` to replace it"
+        );
+        return;
+    }
+
     if aoc_cli::check().is_err() {
         eprintln!(
             "command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it."
@@ -9,7 +20,7 @@ pub fn handle(day: Day) {
         process::exit(1);
     }
 
-    if let Err(e) = aoc_cli::download(day) {
+    if let Err(e) = aoc_cli::download(day, overwrite) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
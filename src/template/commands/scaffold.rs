@@ -1,13 +1,90 @@
 use std::{
+    error::Error,
+    fmt::Display,
     fs::{File, OpenOptions},
     io::Write,
+    path::Path,
     process,
+    str::FromStr,
 };
 
-use crate::template::Day;
+use crate::template::{
+    Day,
+    module_template::{self, DEFAULT_TEMPLATE, TemplateValues},
+};
+
+/// A file created by the `scaffold` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Artifact {
+    Module,
+    Input,
+    Example,
+}
+
+impl Artifact {
+    /// Every artifact, in the order they are created.
+    pub const ALL: [Artifact; 3] = [Artifact::Module, Artifact::Input, Artifact::Example];
+
+    fn path(self, day: Day) -> String {
+        match self {
+            Artifact::Module => format!("src/bin/{day}.rs"),
+            Artifact::Input => format!("data/inputs/{day}.txt"),
+            Artifact::Example => format!("data/examples/{day}.txt"),
+        }
+    }
 
-const MODULE_TEMPLATE: &str =
-    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+    fn description(self) -> &'static str {
+        match self {
+            Artifact::Module => "module file",
+            Artifact::Input => "input file",
+            Artifact::Example => "example file",
+        }
+    }
+}
+
+impl Display for Artifact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Artifact::Module => "module",
+            Artifact::Input => "input",
+            Artifact::Example => "example",
+        })
+    }
+}
+
+impl FromStr for Artifact {
+    type Err = ArtifactFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Artifact::ALL
+            .into_iter()
+            .find(|artifact| artifact.to_string() == s)
+            .ok_or_else(|| ArtifactFromStrError(s.to_string()))
+    }
+}
+
+/// An error which can be returned when parsing an [`Artifact`].
+#[derive(Debug)]
+pub struct ArtifactFromStrError(String);
+
+impl Error for ArtifactFromStrError {}
+
+impl Display for ArtifactFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown artifact \"{}\", expecting a comma-separated list of `module`, `input` and `example`",
+            self.0
+        )
+    }
+}
+
+/// Parses a comma-separated list of artifacts, e.g. `module,example`.
+pub fn parse_artifacts(s: &str) -> Result<Vec<Artifact>, ArtifactFromStrError> {
+    s.split(',')
+        .map(|artifact| artifact.trim().parse())
+        .collect()
+}
 
 fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
     let mut file = OpenOptions::new();
@@ -19,61 +96,107 @@ fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error>
     file.truncate(true).write(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)
-}
-
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+/// Creates the module, input and example files of `day`. Existing files are left untouched
+/// unless their artifact is listed in `overwrite`. With `dry_run`, only reports what would be done.
+///
+/// The puzzle title is read from the downloaded puzzle description, so download it first.
+pub fn handle(
+    day: Day,
+    overwrite: &[Artifact],
+    dry_run: bool,
+    template: Option<&str>,
+    example_answers: [Option<String>; 2],
+) {
+    let template_name = template.unwrap_or(DEFAULT_TEMPLATE);
 
-    let mut file = match safe_create_file(&module_path, overwrite) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
+    // The template is only needed if the module file is going to be written
+    let module_exists = Path::new(&Artifact::Module.path(day)).exists();
+    let template = if module_exists && !overwrite.contains(&Artifact::Module) {
+        None
+    } else {
+        match module_template::load(template_name) {
+            Ok(template) => Some(template),
+            Err(e) => {
+                eprintln!("Failed to load template: {e}");
+                process::exit(1);
+            }
         }
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
+    let values = TemplateValues::for_day(day, example_answers);
+
+    for artifact in Artifact::ALL {
+        let path = artifact.path(day);
+        let description = artifact.description();
+        let exists = Path::new(&path).exists();
+
+        if exists && !overwrite.contains(&artifact) {
+            println!(
+                "Skipped {description} \"{path}\": file already exists, use `--overwrite={artifact}` to replace it"
+            );
+            continue;
         }
-    }
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+        let action = if exists { "overwrite" } else { "create" };
+
+        if dry_run {
+            if artifact == Artifact::Module {
+                println!("Would {action} {description} \"{path}\"");
+            } else {
+                println!("Would {action} empty {description} \"{path}\"");
+            }
+            continue;
         }
-        Err(e) => {
-            eprintln!("Failed to create input file: {e}");
+
+        let mut file = match safe_create_file(&path, exists) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("Failed to {action} {description}: {e}");
+                process::exit(1);
+            }
+        };
+
+        if artifact == Artifact::Module
+            && let Some(template) = &template
+            && let Err(e) = file.write_all(module_template::render(template, &values).as_bytes())
+        {
+            eprintln!("Failed to write module contents: {e}");
             process::exit(1);
         }
-    }
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create example file: {e}");
-            process::exit(1);
+        let action = if exists { "Overwrote" } else { "Created" };
+
+        if artifact == Artifact::Module {
+            println!("{action} {description} \"{path}\" from template \"{template_name}\"");
+        } else {
+            println!("{action} empty {description} \"{path}\"");
         }
     }
 
     println!("---");
-    println!("🎄 Type `cargo solve {day}` to run your solution.");
+    if dry_run {
+        println!("🎄 Dry run, no files were written.");
+    } else {
+        println!("🎄 Type `cargo solve {day}` to run your solution.");
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Artifact, parse_artifacts};
+
+    #[test]
+    fn parses_artifact_lists() {
+        assert_eq!(parse_artifacts("module").unwrap(), vec![Artifact::Module]);
+        assert_eq!(
+            parse_artifacts("module,example, input").unwrap(),
+            vec![Artifact::Module, Artifact::Example, Artifact::Input]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_artifacts() {
+        assert!(parse_artifacts("module,readme").is_err());
+        assert!(parse_artifacts("").is_err());
+    }
 }
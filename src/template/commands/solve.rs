@@ -1,8 +1,15 @@
 use std::process::{Command, Stdio};
 
-use crate::template::Day;
+use crate::template::{Day, watch};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+/// Builds the `cargo run` invocation that runs the solution of `day` against its input.
+pub fn build_command(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    visualize: bool,
+    submit_part: Option<u8>,
+) -> Command {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -16,6 +23,10 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push("--release".to_string());
     }
 
+    if visualize {
+        cmd_args.extend(["--features".to_string(), "visualize".to_string()]);
+    }
+
     cmd_args.push("--".to_string());
 
     if let Some(submit_part) = submit_part {
@@ -23,12 +34,43 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
-    let mut cmd = Command::new("cargo")
-        .args(&cmd_args)
+    let mut cmd = Command::new("cargo");
+    cmd.args(&cmd_args)
         .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    cmd
+}
+
+/// Builds the `cargo test` invocation that checks the solution of `day` against its examples.
+pub fn build_test_command(day: Day, release: bool) -> Command {
+    let mut cmd_args = vec!["test".to_string(), "--bin".to_string(), day.to_string()];
+
+    if release {
+        cmd_args.push("--release".to_string());
+    }
+
+    let mut cmd = Command::new("cargo");
+    cmd.args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    cmd
+}
+
+pub fn handle(day: Day, release: bool, dhat: bool, visualize: bool, submit_part: Option<u8>) {
+    let mut cmd = build_command(day, release, dhat, visualize, submit_part)
         .spawn()
         .unwrap();
 
     cmd.wait().unwrap();
 }
+
+/// Re-runs the solution (or its example tests if `test` is set) every time one of its files changes.
+pub fn handle_watch(day: Day, release: bool, dhat: bool, visualize: bool, test: bool) {
+    watch::watch(day, || {
+        if test {
+            build_test_command(day, release)
+        } else {
+            build_command(day, release, dhat, visualize, None)
+        }
+    });
+}
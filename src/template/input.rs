@@ -0,0 +1,304 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::ops::Deref;
+
+use itertools::Itertools;
+
+/// A puzzle input with normalized line endings, as handed to solutions by [`read_file`](super::read_file) and the
+/// `solution!` macro.
+///
+/// Line endings are `\n`, the input ends with exactly one newline unless it is empty, and a leading byte order mark
+/// is removed. [`Input::padded`] also pads every line with spaces to the length of the longest one.
+/// [`Input::normalizations`] tells what was changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Input {
+    text: String,
+    num_lines: usize,
+    normalizations: Vec<Normalization>,
+}
+
+/// A change applied to the raw text of an [`Input`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Normalization {
+    RemovedByteOrderMark,
+    /// `\r\n` and lone `\r` line endings were replaced by `\n`
+    ConvertedLineEndings {
+        count: usize,
+    },
+    AddedTrailingNewline,
+    RemovedTrailingBlankLines {
+        count: usize,
+    },
+    /// Lines shorter than `width` characters were padded with spaces
+    PaddedLines {
+        count: usize,
+        width: usize,
+    },
+}
+
+impl Display for Normalization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RemovedByteOrderMark => write!(f, "removed the byte order mark"),
+            Self::ConvertedLineEndings { count } => {
+                write!(f, "converted {count} CRLF or CR line endings to LF")
+            }
+            Self::AddedTrailingNewline => write!(f, "added the missing trailing newline"),
+            Self::RemovedTrailingBlankLines { count } => {
+                write!(f, "removed {count} trailing blank lines")
+            }
+            Self::PaddedLines { count, width } => {
+                write!(f, "padded {count} lines with spaces to {width} characters")
+            }
+        }
+    }
+}
+
+impl Input {
+    pub fn new(text: impl Into<String>) -> Self {
+        let mut text: String = text.into();
+        let mut normalizations = vec![];
+
+        if let Some(stripped) = text.strip_prefix('\u{feff}') {
+            text = stripped.to_string();
+            normalizations.push(Normalization::RemovedByteOrderMark);
+        }
+
+        let count = text.matches('\r').count();
+        if count > 0 {
+            text = text.replace("\r\n", "\n").replace('\r', "\n");
+            normalizations.push(Normalization::ConvertedLineEndings { count });
+        }
+
+        let content_len = text.trim_end_matches('\n').len();
+        let newlines = text.len() - content_len;
+        if content_len == 0 {
+            // Nothing but line breaks, there are no lines
+            if newlines > 0 {
+                normalizations.push(Normalization::RemovedTrailingBlankLines { count: newlines });
+            }
+            text.clear();
+        } else if newlines == 0 {
+            text.push('\n');
+            normalizations.push(Normalization::AddedTrailingNewline);
+        } else if newlines > 1 {
+            text.truncate(content_len + 1);
+            normalizations.push(Normalization::RemovedTrailingBlankLines {
+                count: newlines - 1,
+            });
+        }
+
+        let num_lines = text.matches('\n').count();
+        Self {
+            text,
+            num_lines,
+            normalizations,
+        }
+    }
+
+    /// Pads every line with spaces so that they all have as many characters as the longest one.
+    #[must_use]
+    pub fn padded(mut self) -> Self {
+        if let Some((text, normalization)) = self.padded_text() {
+            self.text = text;
+            self.normalizations.push(normalization);
+        }
+        self
+    }
+
+    /// Same as [`Input::padded`] for a borrowed input, e.g. in a solution taking an `&Input`. The input is only
+    /// copied if some lines are padded.
+    pub fn to_padded(&self) -> Cow<'_, Input> {
+        match self.padded_text() {
+            Some((text, normalization)) => {
+                let mut normalizations = self.normalizations.clone();
+                normalizations.push(normalization);
+                Cow::Owned(Self {
+                    text,
+                    num_lines: self.num_lines,
+                    normalizations,
+                })
+            }
+            None => Cow::Borrowed(self),
+        }
+    }
+
+    /// Returns the text with every line padded to the width of the input, `None` if no line is shorter.
+    fn padded_text(&self) -> Option<(String, Normalization)> {
+        let width = self.width();
+        let count = self
+            .lines()
+            .filter(|line| line.chars().count() < width)
+            .count();
+        if count == 0 {
+            return None;
+        }
+
+        let mut text = String::with_capacity(self.num_lines * (width + 1));
+        for line in self.text.lines() {
+            text.push_str(line);
+            text.extend(std::iter::repeat_n(' ', width - line.chars().count()));
+            text.push('\n');
+        }
+        Some((text, Normalization::PaddedLines { count, width }))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    /// Number of lines, blank lines in the middle of the input included.
+    pub fn num_lines(&self) -> usize {
+        self.num_lines
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.text.lines()
+    }
+
+    /// Number of characters of the longest line.
+    pub fn width(&self) -> usize {
+        self.lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns `true` if all lines have the same number of characters.
+    pub fn is_rectangular(&self) -> bool {
+        self.lines().map(|line| line.chars().count()).all_equal()
+    }
+
+    /// Changes that were applied to the raw text, in order.
+    pub fn normalizations(&self) -> &[Normalization] {
+        &self.normalizations
+    }
+}
+
+impl Deref for Input {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl AsRef<str> for Input {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl From<String> for Input {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<&str> for Input {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+/// Argument types a solution can take: the text of the input as a `&str`, or the [`Input`] itself.
+pub trait FromInput<'a>: Copy {
+    fn from_input(input: &'a Input) -> Self;
+}
+
+impl<'a> FromInput<'a> for &'a str {
+    fn from_input(input: &'a Input) -> Self {
+        input.as_str()
+    }
+}
+
+impl<'a> FromInput<'a> for &'a Input {
+    fn from_input(input: &'a Input) -> Self {
+        input
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Input, Normalization};
+    use std::borrow::Cow;
+
+    #[test]
+    fn unchanged_input() {
+        let input = Input::new("ab\n\ncd\n");
+        assert_eq!(input.as_str(), "ab\n\ncd\n");
+        assert_eq!(input.num_lines(), 3);
+        assert!(input.normalizations().is_empty());
+    }
+
+    #[test]
+    fn line_endings() {
+        let input = Input::new("\u{feff}ab\r\ncd\ref");
+        assert_eq!(input.as_str(), "ab\ncd\nef\n");
+        assert_eq!(input.num_lines(), 3);
+        assert_eq!(
+            input.normalizations(),
+            [
+                Normalization::RemovedByteOrderMark,
+                Normalization::ConvertedLineEndings { count: 2 },
+                Normalization::AddedTrailingNewline
+            ]
+        );
+    }
+
+    #[test]
+    fn trailing_blank_lines() {
+        let input = Input::new("ab\r\n\r\n\r\n");
+        assert_eq!(input.as_str(), "ab\n");
+        assert_eq!(input.num_lines(), 1);
+        assert_eq!(
+            input.normalizations()[1],
+            Normalization::RemovedTrailingBlankLines { count: 2 }
+        );
+
+        let input = Input::new("\n\n");
+        assert_eq!(input.as_str(), "");
+        assert_eq!(input.num_lines(), 0);
+        assert_eq!(Input::new("").num_lines(), 0);
+    }
+
+    #[test]
+    fn padded() {
+        let input = Input::new("123 \n 4\n56  \n").padded();
+        assert_eq!(input.as_str(), "123 \n 4  \n56  \n");
+        assert!(input.is_rectangular());
+        assert_eq!(
+            input.normalizations(),
+            [Normalization::PaddedLines { count: 1, width: 4 }]
+        );
+        assert_eq!(
+            input.normalizations()[0].to_string(),
+            "padded 1 lines with spaces to 4 characters"
+        );
+
+        // Borrowed
+        let input = Input::new("123 \n 4\n56  \n");
+        let padded = input.to_padded();
+        assert!(matches!(padded, Cow::Owned(_)));
+        assert_eq!(padded.as_str(), "123 \n 4  \n56  \n");
+        assert_eq!(padded.num_lines(), 3);
+        assert!(input.normalizations().is_empty());
+
+        let rectangle = Input::new("ab\ncd").padded();
+        assert!(matches!(rectangle.to_padded(), Cow::Borrowed(_)));
+        assert_eq!(rectangle.normalizations().len(), 1);
+        assert_eq!(rectangle.width(), 2);
+    }
+}
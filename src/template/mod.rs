@@ -1,57 +1,82 @@
 use std::{env, fs};
 
+use crate::memo;
+
 pub mod aoc_cli;
 pub mod commands;
+pub mod usage;
 pub mod runner;
 
 pub use day::*;
+pub use input::*;
 
 mod day;
+mod input;
+mod module_template;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
+mod watch;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
+pub const ANSI_CLEAR: &str = "\x1b[2J\x1b[H";
 
-/// Helper function that reads a text file to a string.
+/// Helper function that reads a text file to a normalized [`Input`]. The caches of memoized functions are cleared, so
+/// that the values computed for another input aren't reused, see [`memo::clear_all`].
 #[must_use]
-pub fn read_file(folder: &str, day: Day) -> String {
+pub fn read_file(folder: &str, day: Day) -> Input {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    memo::clear_all();
+    Input::new(f.expect("could not open input file"))
 }
 
-/// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
+/// Helper function that reads a text file to a normalized [`Input`], appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
-pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+pub fn read_file_part(folder: &str, day: Day, part: u8) -> Input {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd
         .join("data")
         .join(folder)
         .join(format!("{day}-{part}.txt"));
     let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    memo::clear_all();
+    Input::new(f.expect("could not open input file"))
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
-/// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+/// The optional, second parameter (1 or 2) allows you to only run a single part of the solution. A last `padded`
+/// parameter pads every line of the input to the same width, see [`Input::padded`]. Tests read the examples
+/// themselves, so they need to call `padded` too.
+///
+/// Solutions take the input either as a `&str` or as an [`Input`]. The normalizations applied to the input file are
+/// reported on stderr.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+        $crate::solution!(@impl $day, false, [part_one, 1] [part_two, 2]);
     };
     ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+        $crate::solution!(@impl $day, false, [part_one, 1]);
     };
     ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+        $crate::solution!(@impl $day, false, [part_two, 2]);
+    };
+    ($day:expr, padded) => {
+        $crate::solution!(@impl $day, true, [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, 1, padded) => {
+        $crate::solution!(@impl $day, true, [part_one, 1]);
+    };
+    ($day:expr, 2, padded) => {
+        $crate::solution!(@impl $day, true, [part_two, 2]);
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $padded:expr, $( [$func:expr, $part:expr] )*) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -61,8 +86,12 @@ macro_rules! solution {
 
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            let mut input = $crate::template::read_file("inputs", DAY);
+            if $padded {
+                input = input.padded();
+            }
+            report_normalizations(&input);
+            $( run_part($func, $crate::template::FromInput::from_input(&input), DAY, $part); )*
         }
     };
 }
@@ -0,0 +1,240 @@
+/// Module templates used by the `scaffold` command.
+/// Built-in templates live in `src/templates`, users can add their own (or shadow a built-in one) in `data/templates`.
+use std::{fmt::Display, fs, io};
+
+use crate::template::{Day, aoc_cli};
+
+pub const DEFAULT_TEMPLATE: &str = "default";
+
+static USER_TEMPLATE_DIR: &str = "data/templates";
+
+macro_rules! builtin_template {
+    ($name:literal) => {
+        (
+            $name,
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/templates/",
+                $name,
+                ".txt"
+            )),
+        )
+    };
+}
+
+/// Built-in templates as `(name, contents)` pairs.
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    builtin_template!("default"),
+    builtin_template!("grid"),
+    builtin_template!("lines"),
+    builtin_template!("regex"),
+    builtin_template!("shared_parse"),
+];
+
+#[derive(Debug)]
+pub enum TemplateError {
+    NotFound(String),
+    IO(io::Error),
+}
+
+impl From<io::Error> for TemplateError {
+    fn from(e: io::Error) -> Self {
+        TemplateError::IO(e)
+    }
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::NotFound(name) => write!(
+                f,
+                "unknown template \"{name}\", available templates: {}.",
+                available().join(", ")
+            ),
+            TemplateError::IO(e) => write!(f, "could not read template: {e}"),
+        }
+    }
+}
+
+fn user_template_path(name: &str) -> String {
+    format!("{USER_TEMPLATE_DIR}/{name}.txt")
+}
+
+/// Returns the names of the built-in templates.
+pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+    BUILTIN_TEMPLATES.iter().map(|(name, _)| *name)
+}
+
+/// Returns the names of all built-in and user templates, sorted and without duplicates.
+pub fn available() -> Vec<String> {
+    let mut names: Vec<String> = builtin_names().map(String::from).collect();
+
+    if let Ok(entries) = fs::read_dir(USER_TEMPLATE_DIR) {
+        names.extend(entries.filter_map(Result::ok).filter_map(|entry| {
+            entry
+                .file_name()
+                .to_str()?
+                .strip_suffix(".txt")
+                .map(String::from)
+        }));
+    }
+
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Loads the template called `name`, user templates take precedence over built-in ones.
+pub fn load(name: &str) -> Result<String, TemplateError> {
+    match fs::read_to_string(user_template_path(name)) {
+        Ok(template) => Ok(template),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => BUILTIN_TEMPLATES
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, template)| (*template).to_string())
+            .ok_or_else(|| TemplateError::NotFound(name.to_string())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Values substituted into the placeholders of a template.
+///
+/// | Placeholder           | Value                                                         |
+/// | --------------------- | ------------------------------------------------------------- |
+/// | `%DAY_NUMBER%`        | the day number, e.g. `5`                                      |
+/// | `%DAY%`               | the zero-padded day number, e.g. `05`                         |
+/// | `%YEAR%`              | the value of `AOC_YEAR`                                       |
+/// | `%PUZZLE_TITLE%`      | the puzzle title, if the puzzle description was downloaded    |
+/// | `%EXAMPLE_ANSWER_1%`  | `Some(answer)` if an example answer was given, `None` if not  |
+/// | `%EXAMPLE_ANSWER_2%`  | same as above, for part two                                   |
+pub struct TemplateValues {
+    pub day: Day,
+    pub year: Option<u16>,
+    pub puzzle_title: Option<String>,
+    pub example_answers: [Option<String>; 2],
+}
+
+impl TemplateValues {
+    /// Collects the values for `day` from the environment and the downloaded puzzle description.
+    pub fn for_day(day: Day, example_answers: [Option<String>; 2]) -> Self {
+        let puzzle = fs::read_to_string(aoc_cli::get_puzzle_path(day)).ok();
+        Self::from_puzzle(day, aoc_cli::get_year(), puzzle.as_deref(), example_answers)
+    }
+
+    /// Builds the values for `day` from the puzzle description, if it was downloaded.
+    fn from_puzzle(
+        day: Day,
+        year: Option<u16>,
+        puzzle: Option<&str>,
+        example_answers: [Option<String>; 2],
+    ) -> Self {
+        Self {
+            day,
+            year,
+            puzzle_title: puzzle.and_then(|puzzle| parse_puzzle_title(puzzle, day)),
+            example_answers,
+        }
+    }
+}
+
+/// Extracts the title from a puzzle description, whose heading looks like `--- Day 5: Some Title ---`.
+fn parse_puzzle_title(puzzle: &str, day: Day) -> Option<String> {
+    let heading = format!("Day {}:", day.into_inner());
+
+    puzzle.lines().find_map(|line| {
+        let (_, title) = line.split_once(&heading)?;
+        let title = title
+            .trim_end_matches(|c: char| c == '-' || c == '\\' || c.is_whitespace())
+            .trim();
+        (!title.is_empty()).then(|| title.to_string())
+    })
+}
+
+fn format_example_answer(answer: Option<&String>) -> String {
+    answer.map_or_else(|| "None".to_string(), |answer| format!("Some({answer})"))
+}
+
+/// Replaces every placeholder in `template`.
+pub fn render(template: &str, values: &TemplateValues) -> String {
+    template
+        .replace("%DAY_NUMBER%", &values.day.into_inner().to_string())
+        .replace("%DAY%", &values.day.to_string())
+        .replace(
+            "%YEAR%",
+            &values.year.map(|year| year.to_string()).unwrap_or_default(),
+        )
+        .replace(
+            "%PUZZLE_TITLE%",
+            values.puzzle_title.as_deref().unwrap_or_default(),
+        )
+        .replace(
+            "%EXAMPLE_ANSWER_1%",
+            &format_example_answer(values.example_answers[0].as_ref()),
+        )
+        .replace(
+            "%EXAMPLE_ANSWER_2%",
+            &format_example_answer(values.example_answers[1].as_ref()),
+        )
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{BUILTIN_TEMPLATES, TemplateValues, parse_puzzle_title, render};
+    use crate::day;
+
+    fn get_mock_values() -> TemplateValues {
+        TemplateValues {
+            day: day!(5),
+            year: Some(2025),
+            puzzle_title: Some("Cafeteria".into()),
+            example_answers: [Some("3".into()), None],
+        }
+    }
+
+    #[test]
+    fn renders_all_placeholders() {
+        let rendered = render(
+            "%YEAR% day %DAY_NUMBER% (%DAY%): %PUZZLE_TITLE% %EXAMPLE_ANSWER_1% %EXAMPLE_ANSWER_2%",
+            &get_mock_values(),
+        );
+        assert_eq!(rendered, "2025 day 5 (05): Cafeteria Some(3) None");
+    }
+
+    #[test]
+    fn renders_builtin_templates() {
+        for (name, template) in BUILTIN_TEMPLATES {
+            let rendered = render(template, &get_mock_values());
+            assert!(
+                !rendered.contains('%'),
+                "template {name} has unknown placeholders"
+            );
+            assert!(rendered.contains("advent_of_code::solution!(5);"));
+        }
+    }
+
+    #[test]
+    fn renders_downloaded_puzzle_values() {
+        let template = "// Advent of Code %YEAR%, day %DAY_NUMBER%: %PUZZLE_TITLE%";
+        let puzzle = "\\--- Day 12: Christmas Tree Farm ---\n----------\n";
+
+        let values = TemplateValues::from_puzzle(day!(12), Some(2025), Some(puzzle), [None, None]);
+        assert_eq!(
+            render(template, &values),
+            "// Advent of Code 2025, day 12: Christmas Tree Farm"
+        );
+
+        // Not downloaded yet
+        let values = TemplateValues::from_puzzle(day!(12), None, None, [None, None]);
+        assert_eq!(render(template, &values), "// Advent of Code , day 12: ");
+    }
+
+    #[test]
+    fn parses_puzzle_title() {
+        let puzzle = "\\--- Day 5: Cafeteria ---\n----------\n\nSome description.";
+        assert_eq!(
+            parse_puzzle_title(puzzle, day!(5)),
+            Some("Cafeteria".into())
+        );
+        assert_eq!(parse_puzzle_title(puzzle, day!(6)), None);
+    }
+}
@@ -6,8 +6,9 @@ use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::memo;
 use crate::template::ANSI_BOLD;
-use crate::template::{ANSI_ITALIC, ANSI_RESET, Day, aoc_cli};
+use crate::template::{ANSI_ITALIC, ANSI_RESET, Day, Input, aoc_cli};
 
 pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
@@ -16,12 +17,36 @@ pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, da
         run_timed(func, input, |result| print_result(result, &part_str, ""));
 
     print_result(&result, &part_str, &format_duration(&duration, samples));
+    report_memo_stats();
 
     if let Some(result) = result {
         submit_result(result, day, part);
     }
 }
 
+/// Prints the changes made to the input file while reading it, if any.
+pub fn report_normalizations(input: &Input) {
+    if !input.normalizations().is_empty() {
+        let normalizations = input.normalizations().iter().map(ToString::to_string);
+        eprintln!(
+            "{ANSI_ITALIC}Input normalized: {}{ANSI_RESET}",
+            normalizations.collect::<Vec<_>>().join(", ")
+        );
+    }
+}
+
+/// Prints the cache statistics of the memoized functions called by the last run, in debug builds.
+fn report_memo_stats() {
+    for (name, stats) in memo::stats() {
+        eprintln!(
+            "{ANSI_ITALIC}Memoized {name}: {} hits, {} misses ({:.1}% hits){ANSI_RESET}",
+            stats.hits,
+            stats.misses,
+            100.0 * stats.hit_rate()
+        );
+    }
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
@@ -35,6 +60,8 @@ fn run_timed<I: Copy, T>(
         #[cfg(feature = "dhat-heap")]
         let _profiler = dhat::Profiler::new_heap();
 
+        // Memoized values must not leak from one part or run to the next
+        memo::clear_all();
         func(input)
     };
     let base_time = timer.elapsed();
@@ -62,6 +89,7 @@ fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (
     let mut timers: Vec<Duration> = vec![];
 
     for _ in 0..bench_iterations {
+        memo::clear_all();
         let timer = Instant::now();
         black_box(func(black_box(input)));
         timers.push(timer.elapsed());
@@ -91,6 +119,12 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
 }
 
 fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+    print!("{}", format_result(result, part, duration_str));
+}
+
+/// Formats the line printed for a result. Without `duration_str` it is an intermediate result, shown while
+/// benchmarking and overwritten by the final one.
+fn format_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) -> String {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
@@ -98,28 +132,24 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
             if result.to_string().contains('\n') {
                 let str = format!("{part}: ▼ {duration_str}");
                 if is_intermediate_result {
-                    print!("{str}");
+                    str
                 } else {
-                    print!("\r");
-                    println!("{str}");
-                    println!("{result}");
+                    format!("\r{str}\n{result}\n")
                 }
             } else {
                 let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
                 if is_intermediate_result {
-                    print!("{str}");
+                    str
                 } else {
-                    print!("\r");
-                    println!("{str}");
+                    format!("\r{str}\n")
                 }
             }
         }
         None => {
             if is_intermediate_result {
-                print!("{part}: ✖");
+                format!("{part}: ✖")
             } else {
-                print!("\r");
-                println!("{part}: ✖             ");
+                format!("\r{part}: ✖             \n")
             }
         }
     }
@@ -165,3 +195,36 @@ fn submit_result<T: Display>(
     println!("Submitting result via aoc-cli...");
     Some(aoc_cli::submit(day, part, &result.to_string()))
 }
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_result, run_part, submit_result};
+    use crate::big_uint::BigUint;
+    use crate::day;
+    use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+    fn big_answer() -> BigUint {
+        BigUint::from(u64::MAX) * BigUint::from(u64::MAX)
+    }
+
+    #[test]
+    fn formats_big_uint_results() {
+        assert_eq!(
+            format_result(&Some(big_answer()), "Part 1", " (1.0ms)"),
+            format!(
+                "\rPart 1: {ANSI_BOLD}340282366920938463426481119284349108225{ANSI_RESET} (1.0ms)\n"
+            )
+        );
+        assert_eq!(format_result(&None::<BigUint>, "Part 2", ""), "Part 2: ✖");
+    }
+
+    #[test]
+    fn runs_big_uint_solutions() {
+        run_part(|_: &str| Some(big_answer()), "", day!(1), 1);
+
+        // Only submitted with `--submit`
+        assert!(submit_result(big_answer(), day!(1), 1).is_none());
+    }
+}
@@ -0,0 +1,387 @@
+/// Describes the commands and options of the CLI, used to print help texts and generate shell completions.
+use std::fmt::Write;
+
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+/// What kind of value an argument or option expects, used to complete it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    /// Any day number between 1 and 25.
+    Day,
+    /// The day number of a day whose module exists in `src/bin`.
+    ScaffoldedDay,
+    /// A part number, 1 or 2.
+    Part,
+    /// The name of a scaffold template.
+    Template,
+    /// A shell supported by the `completions` command.
+    Shell,
+    /// A command name.
+    Command,
+    /// A value that can't be completed.
+    Other,
+}
+
+/// A positional argument of a command.
+pub struct ArgUsage {
+    pub name: &'static str,
+    pub required: bool,
+    pub kind: ValueKind,
+    pub about: &'static str,
+}
+
+/// A flag (`value` is `None`) or an option taking a value.
+pub struct OptionUsage {
+    pub flag: &'static str,
+    pub value: Option<(&'static str, ValueKind)>,
+    pub about: &'static str,
+}
+
+/// A subcommand of the CLI.
+pub struct CommandUsage {
+    pub name: &'static str,
+    pub about: &'static str,
+    pub args: &'static [ArgUsage],
+    pub options: &'static [OptionUsage],
+}
+
+const DAY_ARG: ArgUsage = ArgUsage {
+    name: "day",
+    required: true,
+    kind: ValueKind::Day,
+    about: "day number between 1 and 25",
+};
+
+const SCAFFOLDED_DAY_ARG: ArgUsage = ArgUsage {
+    name: "day",
+    required: true,
+    kind: ValueKind::ScaffoldedDay,
+    about: "day number between 1 and 25",
+};
+
+const RELEASE_OPTION: OptionUsage = OptionUsage {
+    flag: "--release",
+    value: None,
+    about: "run an optimized build",
+};
+
+pub const COMMANDS: &[CommandUsage] = &[
+    CommandUsage {
+        name: "scaffold",
+        about: "Create the module, input and example files of a day",
+        args: &[DAY_ARG],
+        options: &[
+            OptionUsage {
+                flag: "--download",
+                value: None,
+                about: "download the input and puzzle description with aoc-cli",
+            },
+            OptionUsage {
+                flag: "--overwrite",
+                value: None,
+                about: "replace the module file if it already exists",
+            },
+            OptionUsage {
+                flag: "--overwrite=",
+                value: Some(("artifacts", ValueKind::Other)),
+                about: "replace the listed files (`module`, `input`, `example`) if they already exist",
+            },
+            OptionUsage {
+                flag: "--dry-run",
+                value: None,
+                about: "print what would be created without writing anything",
+            },
+            OptionUsage {
+                flag: "--template",
+                value: Some(("name", ValueKind::Template)),
+                about: "template of the module file, defaults to `default`",
+            },
+            OptionUsage {
+                flag: "--example-1",
+                value: Some(("answer", ValueKind::Other)),
+                about: "expected answer of part one on the example",
+            },
+            OptionUsage {
+                flag: "--example-2",
+                value: Some(("answer", ValueKind::Other)),
+                about: "expected answer of part two on the example",
+            },
+        ],
+    },
+    CommandUsage {
+        name: "download",
+        about: "Download the input and puzzle description of a day with aoc-cli",
+        args: &[DAY_ARG],
+        options: &[],
+    },
+    CommandUsage {
+        name: "read",
+        about: "Print the puzzle description of a day with aoc-cli",
+        args: &[DAY_ARG],
+        options: &[],
+    },
+    CommandUsage {
+        name: "solve",
+        about: "Run the solution of a day against its input",
+        args: &[SCAFFOLDED_DAY_ARG],
+        options: &[
+            RELEASE_OPTION,
+            OptionUsage {
+                flag: "--dhat",
+                value: None,
+                about: "profile heap allocations with DHAT",
+            },
+            OptionUsage {
+                flag: "--visualize",
+                value: None,
+                about: "draw the grid visualizations of the solution in the terminal",
+            },
+            OptionUsage {
+                flag: "--submit",
+                value: Some(("part", ValueKind::Part)),
+                about: "submit the answer of a part with aoc-cli",
+            },
+            OptionUsage {
+                flag: "--watch",
+                value: None,
+                about: "rerun the solution every time one of its files changes",
+            },
+            OptionUsage {
+                flag: "--test",
+                value: None,
+                about: "with `--watch`, run the example tests instead of the solution",
+            },
+        ],
+    },
+    CommandUsage {
+        name: "all",
+        about: "Run the solutions of all scaffolded days",
+        args: &[],
+        options: &[RELEASE_OPTION],
+    },
+    CommandUsage {
+        name: "time",
+        about: "Benchmark solutions",
+        args: &[ArgUsage {
+            name: "day",
+            required: false,
+            kind: ValueKind::ScaffoldedDay,
+            about: "only benchmark this day",
+        }],
+        options: &[
+            OptionUsage {
+                flag: "--all",
+                value: None,
+                about: "benchmark all days, including the ones already stored",
+            },
+            OptionUsage {
+                flag: "--store",
+                value: None,
+                about: "store the timings in the readme",
+            },
+        ],
+    },
+    CommandUsage {
+        name: "today",
+        about: "Scaffold, download and read the current day (requires the `today` feature)",
+        args: &[],
+        options: &[],
+    },
+    CommandUsage {
+        name: "completions",
+        about: "Print a shell completion script",
+        args: &[ArgUsage {
+            name: "shell",
+            required: true,
+            kind: ValueKind::Shell,
+            about: "one of `bash`, `zsh` or `fish`",
+        }],
+        options: &[],
+    },
+    CommandUsage {
+        name: "help",
+        about: "Print the help of a command",
+        args: &[ArgUsage {
+            name: "command",
+            required: false,
+            kind: ValueKind::Command,
+            about: "command to describe",
+        }],
+        options: &[],
+    },
+];
+
+/// Returns the usage of the command called `name`, if it exists.
+pub fn find_command(name: &str) -> Option<&'static CommandUsage> {
+    COMMANDS.iter().find(|command| command.name == name)
+}
+
+impl ArgUsage {
+    /// `<name>` if the argument is required, `[name]` otherwise.
+    pub fn placeholder(&self) -> String {
+        if self.required {
+            format!("<{}>", self.name)
+        } else {
+            format!("[{}]", self.name)
+        }
+    }
+}
+
+impl OptionUsage {
+    fn signature(&self) -> String {
+        match self.value {
+            Some((name, _)) if self.flag.ends_with('=') => format!("{}<{name}>", self.flag),
+            Some((name, _)) => format!("{} <{name}>", self.flag),
+            None => self.flag.to_string(),
+        }
+    }
+}
+
+impl CommandUsage {
+    /// One-line summary of the arguments, e.g. `solve <day> [options]`.
+    pub fn usage_line(&self) -> String {
+        let mut line = self.name.to_string();
+        for arg in self.args {
+            line.push(' ');
+            line.push_str(&arg.placeholder());
+        }
+        if !self.options.is_empty() {
+            line.push_str(" [options]");
+        }
+        line
+    }
+
+    /// Full help text of the command.
+    pub fn help(&self) -> String {
+        let mut help = format!(
+            "{}\n\n{ANSI_BOLD}Usage:{ANSI_RESET} cargo {}\n",
+            self.about,
+            self.usage_line()
+        );
+
+        if !self.args.is_empty() {
+            help.push_str(&format!("\n{ANSI_BOLD}Arguments:{ANSI_RESET}\n"));
+            let width = self
+                .args
+                .iter()
+                .map(|arg| arg.placeholder().len())
+                .max()
+                .unwrap_or(0);
+            for arg in self.args {
+                let _ = writeln!(help, "  {:width$}  {}", arg.placeholder(), arg.about);
+            }
+        }
+
+        if !self.options.is_empty() {
+            help.push_str(&format!("\n{ANSI_BOLD}Options:{ANSI_RESET}\n"));
+            let width = self
+                .options
+                .iter()
+                .map(|option| option.signature().len())
+                .max()
+                .unwrap_or(0);
+            for option in self.options {
+                let _ = writeln!(help, "  {:width$}  {}", option.signature(), option.about);
+            }
+        }
+
+        help
+    }
+
+    /// Returns the option of this command that is the closest to the unknown `flag`, if any is close enough.
+    pub fn suggest_option(&self, flag: &str) -> Option<&'static str> {
+        let flag = flag.split_once('=').map_or(flag, |(flag, _)| flag);
+        suggest(
+            flag,
+            self.options
+                .iter()
+                .map(|option| option.flag.trim_end_matches('=')),
+        )
+    }
+}
+
+/// Help text listing every command.
+pub fn help() -> String {
+    let mut help =
+        format!("{ANSI_BOLD}Usage:{ANSI_RESET} cargo <command> [arguments] [options]\n\n");
+    help.push_str(&format!("{ANSI_BOLD}Commands:{ANSI_RESET}\n"));
+
+    let width = COMMANDS
+        .iter()
+        .map(|command| command.name.len())
+        .max()
+        .unwrap_or(0);
+    for command in COMMANDS {
+        let _ = writeln!(help, "  {:width$}  {}", command.name, command.about);
+    }
+
+    help.push_str("\nRun `cargo <command> --help` for the arguments and options of a command.\n");
+    help
+}
+
+/// Returns the command name that is the closest to the unknown `name`, if any is close enough.
+pub fn suggest_command(name: &str) -> Option<&'static str> {
+    suggest(name, COMMANDS.iter().map(|command| command.name))
+}
+
+fn suggest(input: &str, candidates: impl Iterator<Item = &'static str>) -> Option<&'static str> {
+    candidates
+        .map(|candidate| (edit_distance(input, candidate), candidate))
+        .filter(|&(distance, _)| distance <= 2)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{edit_distance, find_command, suggest_command};
+
+    #[test]
+    fn computes_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("solve", "solve"), 0);
+        assert_eq!(edit_distance("--relase", "--release"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggests_close_names() {
+        assert_eq!(suggest_command("slove"), Some("solve"));
+        assert_eq!(suggest_command("unrelated"), None);
+
+        let solve = find_command("solve").unwrap();
+        assert_eq!(solve.suggest_option("--relase"), Some("--release"));
+        assert_eq!(solve.suggest_option("--submti=1"), Some("--submit"));
+        assert_eq!(solve.suggest_option("--verbose"), None);
+    }
+
+    #[test]
+    fn formats_usage_lines() {
+        assert_eq!(
+            find_command("solve").unwrap().usage_line(),
+            "solve <day> [options]"
+        );
+        assert_eq!(
+            find_command("time").unwrap().usage_line(),
+            "time [day] [options]"
+        );
+        assert_eq!(find_command("today").unwrap().usage_line(), "today");
+    }
+}
@@ -0,0 +1,140 @@
+/// Polls the files a solution depends on and re-runs it whenever one of them changes.
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Write, stdout},
+    path::PathBuf,
+    process::{self, Child, Command},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::template::{ANSI_CLEAR, ANSI_ITALIC, ANSI_RESET, Day};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Last modification time of every watched file.
+type Snapshot = HashMap<PathBuf, SystemTime>;
+
+/// Returns `true` if `file_name` is a data file of `day`, i.e. `{day}.txt` or `{day}-{part}.txt`.
+fn is_day_data_file(file_name: &str, day: Day) -> bool {
+    let Some(stem) = file_name.strip_suffix(".txt") else {
+        return false;
+    };
+
+    let day = day.to_string();
+
+    match stem.strip_prefix(&day) {
+        Some("") => true,
+        Some(suffix) => suffix
+            .strip_prefix('-')
+            .is_some_and(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit())),
+        None => false,
+    }
+}
+
+/// Lists the files that should trigger a re-run of `day`: its module, the top-level library
+/// modules and its input and example files.
+fn watched_files(day: Day) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(format!("src/bin/{day}.rs"))];
+
+    if let Ok(entries) = fs::read_dir("src") {
+        files.extend(
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "rs")),
+        );
+    }
+
+    for folder in ["inputs", "examples"] {
+        if let Ok(entries) = fs::read_dir(format!("data/{folder}")) {
+            files.extend(
+                entries
+                    .filter_map(Result::ok)
+                    .filter(|entry| is_day_data_file(&entry.file_name().to_string_lossy(), day))
+                    .map(|entry| entry.path()),
+            );
+        }
+    }
+
+    files
+}
+
+fn take_snapshot(day: Day) -> Snapshot {
+    watched_files(day)
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+fn spawn(command: &mut Command) -> Child {
+    print!("{ANSI_CLEAR}");
+    let _ = stdout().flush();
+
+    match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Failed to spawn cargo: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Runs the command returned by `build_command`, then polls the files of `day` and restarts it,
+/// killing the previous run if it is still going, every time one of them changes. Never returns.
+pub fn watch(day: Day, build_command: impl Fn() -> Command) -> ! {
+    let mut snapshot = take_snapshot(day);
+    let mut child = Some(spawn(&mut build_command()));
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        if let Some(running) = &mut child
+            && !matches!(running.try_wait(), Ok(None))
+        {
+            child = None;
+            println!();
+            println!("{ANSI_ITALIC}Watching for changes...{ANSI_RESET}");
+        }
+
+        let new_snapshot = take_snapshot(day);
+
+        if new_snapshot != snapshot {
+            snapshot = new_snapshot;
+
+            if let Some(mut running) = child.take() {
+                // NOTE: `cargo run` replaces itself with the solution binary on unix, so this kills the solution too.
+                let _ = running.kill();
+                let _ = running.wait();
+            }
+
+            child = Some(spawn(&mut build_command()));
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::is_day_data_file;
+    use crate::day;
+
+    #[test]
+    fn matches_day_data_files() {
+        assert!(is_day_data_file("05.txt", day!(5)));
+        assert!(is_day_data_file("05-2.txt", day!(5)));
+        assert!(is_day_data_file("05-12.txt", day!(5)));
+    }
+
+    #[test]
+    fn ignores_other_files() {
+        assert!(!is_day_data_file("06.txt", day!(5)));
+        assert!(!is_day_data_file("05.md", day!(5)));
+        assert!(!is_day_data_file("05-.txt", day!(5)));
+        assert!(!is_day_data_file("05-a.txt", day!(5)));
+        assert!(!is_day_data_file("050.txt", day!(5)));
+    }
+}
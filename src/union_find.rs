@@ -0,0 +1,126 @@
+/// Disjoint-set forest over the elements `0..len`, with union by size and path halving.
+///
+/// Component sizes and the number of components are kept up to date on every union, so querying them is cheap.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    /// Size of the component of each root, meaningless for other elements
+    sizes: Vec<usize>,
+    num_components: usize,
+}
+
+impl UnionFind {
+    /// Creates `len` singleton components.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            num_components: len,
+        }
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Number of components.
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    /// Returns the representative of the component of `x`.
+    pub fn find(&mut self, mut x: usize) -> usize {
+        while self.parents[x] != x {
+            self.parents[x] = self.parents[self.parents[x]];
+            x = self.parents[x];
+        }
+        x
+    }
+
+    /// Merges the components of `a` and `b`, returns `false` if they already were the same component.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        if self.sizes[a] < self.sizes[b] {
+            (a, b) = (b, a);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        self.num_components -= 1;
+        true
+    }
+
+    /// Returns `true` if `a` and `b` are in the same component.
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Returns the number of elements in the component of `x`.
+    pub fn component_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /// Iterates over the representative and size of every component.
+    pub fn components(&self) -> impl Iterator<Item = (usize, usize)> {
+        self.parents
+            .iter()
+            .enumerate()
+            .filter(|&(x, &parent)| x == parent)
+            .map(|(root, _)| (root, self.sizes[root]))
+    }
+
+    /// Returns the sizes of the `k` largest components, largest first.
+    pub fn largest_components(&self, k: usize) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self.components().map(|(_, size)| size).collect();
+        if k < sizes.len() {
+            sizes.select_nth_unstable_by(k, |a, b| b.cmp(a));
+            sizes.truncate(k);
+        }
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union() {
+        let mut union_find = UnionFind::new(6);
+        assert_eq!(union_find.num_components(), 6);
+
+        assert!(union_find.union(0, 1));
+        assert!(union_find.union(2, 1));
+        assert!(!union_find.union(0, 2));
+        assert!(union_find.union(3, 4));
+
+        assert_eq!(union_find.num_components(), 3);
+        assert!(union_find.connected(0, 2));
+        assert!(!union_find.connected(0, 3));
+        assert_eq!(union_find.component_size(1), 3);
+        assert_eq!(union_find.component_size(5), 1);
+    }
+
+    #[test]
+    fn test_largest_components() {
+        let mut union_find = UnionFind::new(8);
+        for (a, b) in [(0, 1), (1, 2), (3, 4), (5, 6), (6, 7), (7, 5), (0, 5)] {
+            union_find.union(a, b);
+        }
+
+        assert_eq!(union_find.largest_components(1), vec![6]);
+        assert_eq!(union_find.largest_components(2), vec![6, 2]);
+        assert_eq!(union_find.largest_components(10), vec![6, 2]);
+        assert_eq!(union_find.components().count(), 2);
+    }
+}
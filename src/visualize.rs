@@ -0,0 +1,270 @@
+/// Animated rendering of grids to the terminal, for debugging.
+///
+/// Drawing only happens when the `visualize` feature is enabled (`cargo solve <day> --visualize`), otherwise every
+/// call is optimized away. Frames are also skipped when benchmarking with `--time` and when stderr is not a
+/// terminal, so visualizations can be left in a solution. Frames are written to stderr to keep stdout parseable.
+use std::fmt::Write;
+use std::io::{self, BufRead, IsTerminal};
+use std::time::Duration;
+use std::{env, thread};
+
+use crate::grid::Grid;
+use crate::template::{ANSI_BOLD, ANSI_CLEAR, ANSI_RESET};
+
+/// A terminal color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    /// 24-bit color, needs a terminal supporting truecolor.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn ansi(self, background: bool) -> String {
+        let base = if background { 40 } else { 30 };
+        match self {
+            Color::Black => format!("\x1b[{base}m"),
+            Color::Red => format!("\x1b[{}m", base + 1),
+            Color::Green => format!("\x1b[{}m", base + 2),
+            Color::Yellow => format!("\x1b[{}m", base + 3),
+            Color::Blue => format!("\x1b[{}m", base + 4),
+            Color::Magenta => format!("\x1b[{}m", base + 5),
+            Color::Cyan => format!("\x1b[{}m", base + 6),
+            Color::White => format!("\x1b[{}m", base + 7),
+            Color::Rgb(r, g, b) => format!("\x1b[{};2;{r};{g};{b}m", base + 8),
+        }
+    }
+}
+
+/// How a single cell is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    pub symbol: char,
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    /// Draws `symbol` without any color.
+    pub fn new(symbol: char) -> Self {
+        Self {
+            symbol,
+            foreground: None,
+            background: None,
+            bold: false,
+        }
+    }
+
+    pub fn fg(mut self, color: Color) -> Self {
+        self.foreground = Some(color);
+        self
+    }
+
+    pub fn bg(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    fn write_to(self, text: &mut String) {
+        if self == Style::new(self.symbol) {
+            text.push(self.symbol);
+            return;
+        }
+
+        if let Some(color) = self.foreground {
+            text.push_str(&color.ansi(false));
+        }
+        if let Some(color) = self.background {
+            text.push_str(&color.ansi(true));
+        }
+        if self.bold {
+            text.push_str(ANSI_BOLD);
+        }
+        text.push(self.symbol);
+        text.push_str(ANSI_RESET);
+    }
+}
+
+impl From<char> for Style {
+    fn from(symbol: char) -> Self {
+        Style::new(symbol)
+    }
+}
+
+/// Renders `grid` to a string with ANSI escape codes, one line per row.
+pub fn render<T>(grid: &Grid<T>, mut style: impl FnMut(&T) -> Style) -> String {
+    let mut text = String::new();
+    for (i, row) in grid.rows().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        for cell in row {
+            style(cell).write_to(&mut text);
+        }
+    }
+    text
+}
+
+/// When to move on to the next frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepMode {
+    /// Wait for the delay, then continue.
+    Continuous,
+    /// Wait for a command on stdin: `Enter` shows the next frame, a number `n` skips `n` frames,
+    /// `c` switches to continuous mode and `q` stops drawing.
+    Manual,
+}
+
+/// Draws successive states of a grid as an animation.
+///
+/// ```ignore
+/// let mut visualizer = Visualizer::new().delay(Duration::from_millis(50)).enabled(!cfg!(test));
+/// automaton.run_until_fixpoint_with(|_, grid, _| {
+///     visualizer.frame(grid, |&roll| if roll { Style::new('@').fg(Color::Green) } else { '.'.into() })
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Visualizer {
+    delay: Duration,
+    mode: StepMode,
+    frame: usize,
+    skipped_frames: usize,
+    enabled: bool,
+}
+
+impl Default for Visualizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Visualizer {
+    /// Creates a continuous visualizer drawing a frame every 100ms.
+    pub fn new() -> Self {
+        Self {
+            delay: Duration::from_millis(100),
+            mode: StepMode::Continuous,
+            frame: 0,
+            skipped_frames: 0,
+            enabled: is_enabled(),
+        }
+    }
+
+    /// Sets the time each frame stays on screen.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn mode(mut self, mode: StepMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Only draws frames if `enabled` is `true` as well, e.g. `!cfg!(test)` so that the tests of a solution don't wait
+    /// for the animation.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled &= enabled;
+        self
+    }
+
+    /// Returns `false` if frames are not drawn, e.g. to skip computing an expensive state only used for drawing.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Clears the terminal, draws `grid` with `style` and waits according to the step mode.
+    pub fn frame<T>(&mut self, grid: &Grid<T>, style: impl FnMut(&T) -> Style) {
+        if cfg!(feature = "visualize") && self.enabled {
+            self.draw(&render(grid, style));
+        }
+    }
+
+    fn draw(&mut self, rendered: &str) {
+        self.frame += 1;
+        if self.skipped_frames > 0 {
+            self.skipped_frames -= 1;
+            return;
+        }
+
+        let mut text = String::new();
+        let _ = write!(
+            text,
+            "{ANSI_CLEAR}{ANSI_BOLD}Frame {}{ANSI_RESET}",
+            self.frame
+        );
+        if self.mode == StepMode::Manual {
+            text.push_str(" (Enter: next, <n>: skip n frames, c: continue, q: quit)");
+        }
+        let _ = write!(text, "\n{rendered}\n");
+        eprint!("{text}");
+
+        match self.mode {
+            StepMode::Continuous => thread::sleep(self.delay),
+            StepMode::Manual => {
+                let mut command = String::new();
+                let _ = io::stdin().lock().read_line(&mut command);
+                match command.trim() {
+                    "c" => self.mode = StepMode::Continuous,
+                    "q" => self.enabled = false,
+                    n => self.skipped_frames = n.parse().unwrap_or(0),
+                }
+            }
+        }
+    }
+}
+
+/// Frames are drawn if the `visualize` feature is enabled, the solution isn't benchmarked and stderr is a terminal.
+fn is_enabled() -> bool {
+    cfg!(feature = "visualize") && !is_benchmarking() && io::stderr().is_terminal()
+}
+
+/// Returns `true` if the solution is run with `--time`.
+pub(crate) fn is_benchmarking() -> bool {
+    env::args().any(|arg| arg == "--time")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let grid = Grid::parse("@.\n.@", |c| c == '@');
+        let rendered = render(&grid, |&roll| {
+            if roll {
+                Style::new('@').fg(Color::Red).bold()
+            } else {
+                '.'.into()
+            }
+        });
+        assert_eq!(
+            rendered,
+            "\x1b[31m\x1b[1m@\x1b[0m.\n.\x1b[31m\x1b[1m@\x1b[0m"
+        );
+        assert_eq!(
+            render(&grid, |_| Style::new(' ').bg(Color::Rgb(1, 2, 3))),
+            "\x1b[48;2;1;2;3m \x1b[0m\x1b[48;2;1;2;3m \x1b[0m\n\x1b[48;2;1;2;3m \x1b[0m\x1b[48;2;1;2;3m \x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_enabled() {
+        let visualizer = Visualizer::new().enabled(false);
+        assert!(!visualizer.is_enabled());
+        assert!(!visualizer.enabled(true).is_enabled());
+    }
+}